@@ -0,0 +1,336 @@
+//! Dynamic call builders for the bridge pallet.
+//!
+//! We don't ship generated metadata for the Etrid runtime, so every call is
+//! built through `subxt::dynamic` and checked against the node's metadata at
+//! submission time.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use subxt::config::substrate::BlakeTwo256;
+use subxt::config::Hasher;
+use subxt::dynamic::Value;
+use subxt::tx::DynamicPayload;
+use subxt::utils::AccountId32;
+
+use crate::{units, BridgeConfiguration, ChainConfig, ConfirmationBlocks};
+
+/// Pallet that owns token mappings and bridge parameters
+pub const BRIDGE_PALLET: &str = "Bridge";
+
+/// Pallet used to group several calls into one extrinsic
+pub const UTILITY_PALLET: &str = "Utility";
+
+/// Pallet used to dispatch calls as root during network bring-up
+pub const SUDO_PALLET: &str = "Sudo";
+
+/// Build `bridge.set_token_mapping(chain, token_address, exchange_rate, decimals, dest_decimals)`
+pub fn set_token_mapping(chain: &str, chain_config: &ChainConfig) -> Result<DynamicPayload> {
+    let exchange_rate = chain_config.runtime_exchange_rate()
+        .context(format!("Invalid exchange rate for {}", chain))?;
+    chain_config.check_decimals()
+        .context(format!("Invalid token decimals for {}", chain))?;
+    chain_config.check_identity_rate_decimals()
+        .context(format!("Invalid token decimals for {}", chain))?;
+    if crate::chains::is_placeholder(&chain_config.token_address) {
+        anyhow::bail!("token_address {} for {} is a placeholder, not a deployed token", chain_config.token_address, chain);
+    }
+
+    Ok(subxt::dynamic::tx(
+        BRIDGE_PALLET,
+        "set_token_mapping",
+        vec![
+            Value::from_bytes(chain),
+            Value::from_bytes(&chain_config.token_address),
+            Value::u128(exchange_rate),
+            Value::u128(chain_config.source_decimals as u128),
+            Value::u128(chain_config.dest_decimals() as u128),
+        ],
+    ))
+}
+
+/// Build `bridge.set_fee(chain, fee)` with the fee as a `Permill`
+pub fn set_fee(chain: &str, fee_permill: u32) -> DynamicPayload {
+    subxt::dynamic::tx(
+        BRIDGE_PALLET,
+        "set_fee",
+        vec![Value::from_bytes(chain), Value::u128(fee_permill as u128)],
+    )
+}
+
+/// Build `bridge.set_max_amount(chain, amount)`
+pub fn set_max_amount(chain: &str, amount: u128) -> DynamicPayload {
+    subxt::dynamic::tx(
+        BRIDGE_PALLET,
+        "set_max_amount",
+        vec![Value::from_bytes(chain), Value::u128(amount)],
+    )
+}
+
+/// Build `bridge.set_min_amount(chain, amount)`
+pub fn set_min_amount(chain: &str, amount: u128) -> DynamicPayload {
+    subxt::dynamic::tx(
+        BRIDGE_PALLET,
+        "set_min_amount",
+        vec![Value::from_bytes(chain), Value::u128(amount)],
+    )
+}
+
+/// Build `bridge.set_confirmations(chain, confirmations)`
+pub fn set_confirmations(chain: &str, confirmations: u32) -> DynamicPayload {
+    subxt::dynamic::tx(
+        BRIDGE_PALLET,
+        "set_confirmations",
+        vec![Value::from_bytes(chain), Value::u128(confirmations as u128)],
+    )
+}
+
+/// Build `bridge.set_withdrawal_confirmations(chain, confirmations)`, the
+/// depth for withdrawals when it differs from `set_confirmations`' deposits
+pub fn set_withdrawal_confirmations(chain: &str, confirmations: u32) -> DynamicPayload {
+    subxt::dynamic::tx(
+        BRIDGE_PALLET,
+        "set_withdrawal_confirmations",
+        vec![Value::from_bytes(chain), Value::u128(confirmations as u128)],
+    )
+}
+
+/// The calls that set `confirmations`: the deposit depth goes to
+/// `set_confirmations` and the withdrawal depth to `set_withdrawal_confirmations`.
+/// One number sets both, so no stale withdrawal depth is left behind.
+pub fn confirmation_calls(chain: &str, confirmations: ConfirmationBlocks) -> Vec<DynamicPayload> {
+    vec![
+        set_confirmations(chain, confirmations.deposit()),
+        set_withdrawal_confirmations(chain, confirmations.withdrawal()),
+    ]
+}
+
+/// Build `bridge.add_relayer(chain, relayer)`, registering `relayer` in `Bridge::Relayers`
+pub fn add_relayer(chain: &str, relayer: &AccountId32) -> DynamicPayload {
+    subxt::dynamic::tx(
+        BRIDGE_PALLET,
+        "add_relayer",
+        vec![Value::from_bytes(chain), Value::from_bytes(relayer.0)],
+    )
+}
+
+/// Build `bridge.set_relayer_threshold(chain, threshold)`, the number of
+/// relayers that must attest a transfer
+pub fn set_relayer_threshold(chain: &str, threshold: u32) -> DynamicPayload {
+    subxt::dynamic::tx(
+        BRIDGE_PALLET,
+        "set_relayer_threshold",
+        vec![Value::from_bytes(chain), Value::u128(threshold as u128)],
+    )
+}
+
+/// Build `bridge.set_relayer_weight(chain, relayer, weight)`
+pub fn set_relayer_weight(chain: &str, relayer: &AccountId32, weight: u32) -> DynamicPayload {
+    subxt::dynamic::tx(
+        BRIDGE_PALLET,
+        "set_relayer_weight",
+        vec![Value::from_bytes(chain), Value::from_bytes(relayer.0), Value::u128(weight as u128)],
+    )
+}
+
+/// Build `bridge.unpause(chain)`, which lets `chain`'s transfers through again
+pub fn unpause(chain: &str) -> DynamicPayload {
+    subxt::dynamic::tx(BRIDGE_PALLET, "unpause", vec![Value::from_bytes(chain)])
+}
+
+/// Highest confirmation depth accepted by `update confirmations`
+pub const MAX_CONFIRMATIONS: u32 = 10_000;
+
+/// A validated value for `pbc-config update`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateValue {
+    MaxAmount(u128),
+    MinAmount(u128),
+    Fee(u32),
+    Confirmations(u32),
+}
+
+impl UpdateValue {
+    /// Validate `value` for `parameter`, without touching the network
+    pub fn parse(parameter: &str, value: &str) -> Result<Self> {
+        match parameter {
+            "max-amount" => Ok(Self::MaxAmount(units::parse_amount(value)
+                .context("Invalid max-amount")?)),
+            "min-amount" => Ok(Self::MinAmount(units::parse_amount(value)
+                .context("Invalid min-amount")?)),
+            "fee" => Ok(Self::Fee(units::percent_to_permill(value)
+                .context("Invalid fee")?)),
+            "confirmations" => {
+                let confirmations: u32 = value.trim().parse().context(format!(
+                    "Invalid confirmations `{}`, expected an integer from 1 to {}",
+                    value, MAX_CONFIRMATIONS
+                ))?;
+                if !(1..=MAX_CONFIRMATIONS).contains(&confirmations) {
+                    anyhow::bail!(
+                        "Confirmations {} is out of range, expected 1 to {}",
+                        confirmations, MAX_CONFIRMATIONS
+                    );
+                }
+                Ok(Self::Confirmations(confirmations))
+            }
+            _ => anyhow::bail!(
+                "Unknown parameter: {} (expected max-amount, min-amount, fee or confirmations)",
+                parameter
+            ),
+        }
+    }
+
+    /// Build the call that applies this value to `chain`
+    pub fn call(&self, chain: &str) -> DynamicPayload {
+        match *self {
+            Self::MaxAmount(amount) => set_max_amount(chain, amount),
+            Self::MinAmount(amount) => set_min_amount(chain, amount),
+            Self::Fee(fee_permill) => set_fee(chain, fee_permill),
+            Self::Confirmations(confirmations) => set_confirmations(chain, confirmations),
+        }
+    }
+}
+
+/// The value as a user would write it: amounts in base units, the fee as a percent
+impl std::fmt::Display for UpdateValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::MaxAmount(amount) | Self::MinAmount(amount) => write!(f, "{}", amount),
+            Self::Fee(fee_permill) => write!(f, "{}", units::permill_to_percent(fee_permill)),
+            Self::Confirmations(confirmations) => write!(f, "{}", confirmations),
+        }
+    }
+}
+
+/// Build every call needed to configure `chain`, wrapped in one `utility.batch_all`
+/// so the chain is either fully configured or not at all. `relayer_weights` are
+/// the configured relayers that carry a weight.
+pub fn configure_chain(
+    chain: &str,
+    chain_config: &ChainConfig,
+    bridge_config: &BridgeConfiguration,
+    relayer_weights: &[(AccountId32, u32)],
+) -> Result<DynamicPayload> {
+    Ok(batch_all(configure_calls(chain, chain_config, bridge_config, relayer_weights)?))
+}
+
+/// The calls [`configure_chain`] batches, for callers that add their own
+pub fn configure_calls(
+    chain: &str,
+    chain_config: &ChainConfig,
+    bridge_config: &BridgeConfiguration,
+    relayer_weights: &[(AccountId32, u32)],
+) -> Result<Vec<DynamicPayload>> {
+    let fee_permill = units::percent_to_permill(&bridge_config.bridge_fee_percent)
+        .context("Invalid bridge_fee_percent")?;
+
+    let confirmations = bridge_config.confirmations_for(chain)?;
+
+    let mut calls = vec![
+        set_token_mapping(chain, chain_config)?,
+        set_fee(chain, fee_permill),
+    ];
+    calls.extend(confirmation_calls(chain, confirmations));
+    calls.extend(relayer_weights.iter().map(|(relayer, weight)| set_relayer_weight(chain, relayer, *weight)));
+    Ok(calls)
+}
+
+/// Blake2-256 hash of SCALE-encoded `call_data`, hex with a `0x` prefix.
+/// Governance identifies a call by this hash.
+pub fn call_hash(call_data: &[u8]) -> String {
+    format!("0x{}", hex::encode(BlakeTwo256::hash(call_data).0))
+}
+
+/// Wrap `call` into `sudo.sudo(call)` so it dispatches with root origin
+pub fn sudo(call: DynamicPayload) -> DynamicPayload {
+    subxt::dynamic::tx(SUDO_PALLET, "sudo", vec![call.into_value()])
+}
+
+/// Wrap several calls into `utility.batch_all(calls)`, which reverts them all if any fails
+pub fn batch_all(calls: Vec<DynamicPayload>) -> DynamicPayload {
+    let calls: Vec<Value> = calls.into_iter().map(|call| call.into_value()).collect();
+
+    subxt::dynamic::tx(
+        UTILITY_PALLET,
+        "batch_all",
+        vec![Value::unnamed_composite(calls)],
+    )
+}
+
+/// Wrap several calls into `utility.batch(calls)`.
+///
+/// `batch` (rather than `batch_all`) keeps the calls that succeeded before an
+/// interruption, which is what lets us report outcomes per chain.
+pub fn batch(calls: Vec<DynamicPayload>) -> DynamicPayload {
+    let calls: Vec<Value> = calls.into_iter().map(|call| call.into_value()).collect();
+
+    subxt::dynamic::tx(
+        UTILITY_PALLET,
+        "batch",
+        vec![Value::unnamed_composite(calls)],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sudo_wraps_call() {
+        let call = sudo(set_fee("tron", 1_000));
+        assert_eq!((call.pallet_name(), call.call_name()), (SUDO_PALLET, "sudo"));
+    }
+
+    #[test]
+    fn test_confirmation_calls() {
+        let names = |confirmations| confirmation_calls("bitcoin", confirmations)
+            .iter()
+            .map(|call| call.call_name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names(ConfirmationBlocks::Both(6)), ["set_confirmations", "set_withdrawal_confirmations"]);
+        assert_eq!(
+            names(ConfirmationBlocks::PerDirection { deposit: 6, withdrawal: 3 }),
+            ["set_confirmations", "set_withdrawal_confirmations"]
+        );
+    }
+
+    #[test]
+    fn test_unpause() {
+        let call = unpause("tron");
+        assert_eq!((call.pallet_name(), call.call_name()), (BRIDGE_PALLET, "unpause"));
+    }
+
+    #[test]
+    fn test_relayer_calls() {
+        let call = add_relayer("tron", &AccountId32([1; 32]));
+        assert_eq!((call.pallet_name(), call.call_name()), (BRIDGE_PALLET, "add_relayer"));
+        let call = set_relayer_threshold("tron", 2);
+        assert_eq!((call.pallet_name(), call.call_name()), (BRIDGE_PALLET, "set_relayer_threshold"));
+    }
+
+    #[test]
+    fn test_call_hash() {
+        assert_eq!(call_hash(&[]), "0x0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8");
+        assert_ne!(call_hash(&[0x2a, 0x00]), call_hash(&[0x2a, 0x01]));
+    }
+
+    #[test]
+    fn test_parse_update_value() {
+        assert_eq!(UpdateValue::parse("max-amount", "1_000").unwrap(), UpdateValue::MaxAmount(1_000));
+        assert_eq!(UpdateValue::parse("fee", "0.3").unwrap(), UpdateValue::Fee(3_000));
+        assert_eq!(UpdateValue::parse("confirmations", "12").unwrap(), UpdateValue::Confirmations(12));
+
+        assert!(UpdateValue::parse("min-amount", "-1").is_err());
+        assert!(UpdateValue::parse("fee", "101").is_err());
+        assert!(UpdateValue::parse("confirmations", "0").is_err());
+        assert!(UpdateValue::parse("confirmations", "10001").is_err());
+        assert!(UpdateValue::parse("relayers", "1").is_err());
+    }
+
+    #[test]
+    fn test_display_update_value() {
+        assert_eq!(UpdateValue::Fee(3_000).to_string(), "0.3%");
+        assert_eq!(UpdateValue::MaxAmount(1_000_000).to_string(), "1000000");
+        assert_eq!(UpdateValue::Confirmations(12).to_string(), "12");
+    }
+}
@@ -0,0 +1,437 @@
+//! Per-chain-family rules for addresses, token decimals and confirmations.
+//!
+//! Each external chain family implements [`ChainAdapter`]; [`adapter`] looks
+//! up the implementation for a configured chain name. Supporting a new chain
+//! means implementing the trait and adding a row to `REGISTRY`.
+
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+pub trait ChainAdapter: Sync {
+    /// Chain family, for messages
+    fn family(&self) -> &'static str;
+
+    /// Check `address` is a well-formed token address (contract, mint or issuer)
+    fn validate_token_address(&self, address: &str) -> Result<()>;
+
+    /// Check `address` is a well-formed address for the bridge's account or contract
+    fn validate_bridge_address(&self, address: &str) -> Result<()> {
+        self.validate_token_address(address)
+    }
+
+    /// Decimals of `token_address` when the chain itself fixes them (e.g. BTC's 8).
+    /// `None` when only a query against the token's chain could tell.
+    fn fetch_token_decimals(&self, _token_address: &str) -> Option<u8> {
+        None
+    }
+
+    /// `address` in the casing this family writes it in, so configs diff cleanly.
+    /// Case-sensitive encodings such as base58 come back unchanged.
+    fn canonical_address(&self, address: &str) -> String {
+        address.to_string()
+    }
+
+    /// How to ask the chain's own node whether the bridge at `address` is
+    /// deployed. `None` when there is nothing to deploy (e.g. a Bitcoin address).
+    fn bridge_existence_query(&self, _address: &str) -> Option<ExistenceQuery> {
+        None
+    }
+
+    /// What one confirmation is on this chain, singular and plural
+    fn confirmation_unit(&self) -> (&'static str, &'static str) {
+        ("block", "blocks")
+    }
+
+    /// Fewest confirmations that make a transfer safe to act on
+    fn min_confirmations(&self) -> u32 {
+        1
+    }
+
+    /// Roughly how long one confirmation takes, when the family fixes it
+    fn confirmation_interval(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// A JSON-RPC call to an external chain's node that shows whether an address
+/// is deployed: a contract with code, or an account that exists
+pub struct ExistenceQuery {
+    pub method: &'static str,
+    pub params: serde_json::Value,
+    /// Whether the call's `result` shows the address exists
+    pub exists: fn(&serde_json::Value) -> bool,
+}
+
+/// `eth_getCode` at the latest block, for EVM-style JSON-RPC
+fn get_code_query(hex_address: String) -> ExistenceQuery {
+    ExistenceQuery {
+        method: "eth_getCode",
+        params: serde_json::json!([hex_address, "latest"]),
+        // No code comes back as "0x" (or "0x0" from some nodes)
+        exists: |result| result.as_str().is_some_and(|code| !code.trim_start_matches("0x").trim_matches('0').is_empty()),
+    }
+}
+
+/// Ethereum and EVM-compatible chains: `0x` and 20 bytes of hex
+pub struct Evm;
+
+impl ChainAdapter for Evm {
+    fn family(&self) -> &'static str {
+        "EVM"
+    }
+
+    fn validate_token_address(&self, address: &str) -> Result<()> {
+        let hex_part = address.strip_prefix("0x")
+            .context(format!("EVM address {} must start with 0x", address))?;
+        if hex_part.len() != 40 || !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
+            anyhow::bail!("EVM address {} must be 0x followed by 40 hex digits", address);
+        }
+        Ok(())
+    }
+
+    fn canonical_address(&self, address: &str) -> String {
+        match address.strip_prefix("0x") {
+            Some(hex_part) => format!("0x{}", hex_part.to_ascii_lowercase()),
+            None => address.to_string(),
+        }
+    }
+
+    fn bridge_existence_query(&self, address: &str) -> Option<ExistenceQuery> {
+        Some(get_code_query(address.to_string()))
+    }
+}
+
+/// Solana: a base58-encoded 32-byte public key
+pub struct Solana;
+
+impl ChainAdapter for Solana {
+    fn family(&self) -> &'static str {
+        "Solana"
+    }
+
+    fn validate_token_address(&self, address: &str) -> Result<()> {
+        let bytes = bs58::decode(address).into_vec()
+            .context(format!("Solana address {} is not valid base58", address))?;
+        if bytes.len() != 32 {
+            anyhow::bail!("Solana address {} decodes to {} bytes, expected 32", address, bytes.len());
+        }
+        Ok(())
+    }
+
+    fn bridge_existence_query(&self, address: &str) -> Option<ExistenceQuery> {
+        Some(ExistenceQuery {
+            method: "getAccountInfo",
+            params: serde_json::json!([address, { "encoding": "base64" }]),
+            exists: |result| !result["value"].is_null(),
+        })
+    }
+}
+
+/// Tron: base58check with the 0x41 mainnet prefix (`T...`)
+pub struct Tron;
+
+impl ChainAdapter for Tron {
+    fn family(&self) -> &'static str {
+        "Tron"
+    }
+
+    fn validate_token_address(&self, address: &str) -> Result<()> {
+        base58check_account(address, bs58::Alphabet::BITCOIN, &[0x41])
+            .context(format!("Invalid Tron address {}", address))
+    }
+
+    fn bridge_existence_query(&self, address: &str) -> Option<ExistenceQuery> {
+        // Tron's JSON-RPC speaks Ethereum's, taking the 20 bytes behind the 0x41 prefix
+        let bytes = bs58::decode(address).with_check(None).into_vec().ok()?;
+        let (_, account) = bytes.split_first()?;
+        Some(get_code_query(format!("0x{}", hex::encode(account))))
+    }
+}
+
+/// XRP Ledger: base58check in the Ripple alphabet (`r...`)
+pub struct Xrp;
+
+impl ChainAdapter for Xrp {
+    fn family(&self) -> &'static str {
+        "XRP Ledger"
+    }
+
+    fn confirmation_unit(&self) -> (&'static str, &'static str) {
+        // Consensus closes a ledger every few seconds, and a validated one is final
+        ("validated ledger", "validated ledgers")
+    }
+
+    fn confirmation_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(4))
+    }
+
+    fn validate_token_address(&self, address: &str) -> Result<()> {
+        base58check_account(address, bs58::Alphabet::RIPPLE, &[0x00])
+            .context(format!("Invalid XRP Ledger address {}", address))
+    }
+
+    fn bridge_existence_query(&self, address: &str) -> Option<ExistenceQuery> {
+        Some(ExistenceQuery {
+            method: "account_info",
+            params: serde_json::json!([{ "account": address, "ledger_index": "validated" }]),
+            // A missing account is reported inside `result` as `actNotFound`
+            exists: |result| result.get("account_data").is_some(),
+        })
+    }
+}
+
+/// Bitcoin mainnet: segwit (`bc1...`) or legacy P2PKH/P2SH addresses
+pub struct Bitcoin;
+
+impl ChainAdapter for Bitcoin {
+    fn family(&self) -> &'static str {
+        "Bitcoin"
+    }
+
+    fn confirmation_unit(&self) -> (&'static str, &'static str) {
+        // A UTXO is only as final as the proof-of-work stacked on its block
+        ("block deep", "blocks deep")
+    }
+
+    fn min_confirmations(&self) -> u32 {
+        6
+    }
+
+    fn confirmation_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(600))
+    }
+
+    fn validate_token_address(&self, address: &str) -> Result<()> {
+        if address.to_ascii_lowercase().starts_with("bc1") {
+            let (hrp, _, _) = bech32::segwit::decode(address)
+                .map_err(|e| anyhow::anyhow!("Invalid Bitcoin segwit address {}: {}", address, e))?;
+            if hrp != bech32::hrp::BC {
+                anyhow::bail!("Bitcoin address {} is not a mainnet address", address);
+            }
+            return Ok(());
+        }
+        base58check_account(address, bs58::Alphabet::BITCOIN, &[0x00, 0x05])
+            .context(format!("Invalid Bitcoin address {}", address))
+    }
+
+    fn fetch_token_decimals(&self, _token_address: &str) -> Option<u8> {
+        // The bridged asset is BTC itself, counted in satoshis
+        Some(8)
+    }
+
+    fn canonical_address(&self, address: &str) -> String {
+        // Bech32 is case-insensitive and written in lowercase; base58 is case-sensitive
+        if address.to_ascii_lowercase().starts_with("bc1") {
+            address.to_ascii_lowercase()
+        } else {
+            address.to_string()
+        }
+    }
+}
+
+/// Chain names in the config and the adapter that handles each
+const REGISTRY: &[(&str, &dyn ChainAdapter)] = &[
+    ("ethereum", &Evm),
+    ("bnb", &Evm),
+    ("polygon", &Evm),
+    ("solana", &Solana),
+    ("tron", &Tron),
+    ("xrp", &Xrp),
+    ("bitcoin", &Bitcoin),
+];
+
+/// The adapter for a configured chain name, if it is a known chain
+pub fn adapter(chain: &str) -> Option<&'static dyn ChainAdapter> {
+    REGISTRY.iter()
+        .find(|(name, _)| *name == chain)
+        .map(|(_, adapter)| *adapter)
+}
+
+/// `blocks` confirmations in `chain`'s own terms, e.g. "6 blocks deep (~60 min)"
+/// or "1 validated ledger (~4 s)"; plain "N confirmations" for unknown chains
+pub fn describe_confirmations(chain: &str, blocks: u32) -> String {
+    let Some(adapter) = adapter(chain) else {
+        return format!("{} confirmations", blocks);
+    };
+    let (singular, plural) = adapter.confirmation_unit();
+    let unit = if blocks == 1 { singular } else { plural };
+    match adapter.confirmation_interval() {
+        Some(interval) => {
+            let total = interval.as_secs() * u64::from(blocks);
+            let approx = if total >= 120 { format!("{} min", total / 60) } else { format!("{} s", total) };
+            format!("{} {} (~{})", blocks, unit, approx)
+        }
+        None => format!("{} {}", blocks, unit),
+    }
+}
+
+/// Fail if `blocks` is below the minimum for `chain`'s family
+pub fn check_min_confirmations(chain: &str, blocks: u32) -> Result<()> {
+    let Some(adapter) = adapter(chain) else {
+        return Ok(());
+    };
+    let min = adapter.min_confirmations();
+    if blocks < min {
+        anyhow::bail!(
+            "{} is below the {} minimum of {}",
+            describe_confirmations(chain, blocks),
+            adapter.family(),
+            describe_confirmations(chain, min)
+        );
+    }
+    Ok(())
+}
+
+/// The made-up addresses the config templates ship with. Each is well-formed
+/// for its family, so it passes the same format checks as a deployed address.
+const PLACEHOLDER_ADDRESSES: [&str; 9] = [
+    "0x1234567890123456789012345678901234567890",
+    "0x2345678901234567890123456789012345678901",
+    "0x3456789012345678901234567890123456789012",
+    "BridgeGov1111111111111111111111111111111111",
+    "TBdTs1DEqhbGbTdoAmrpNtjzBm1FT7wnBs",
+    "rhDVopnuKQnDw9rZDerJNkWo8r1r8wgSaa",
+    "rnmjjnkiCrNbtL15S4RfFkgLwNHLf7CHqV",
+    "bc1qg4ncjqfrg4ncjqfrg4ncjqfrg4ncjqfrp5gncg",
+    "bc1q2eufqy352eufqy352eufqy352eufqy35c4n4ue",
+];
+
+/// Whether `address` is one of the template values in [`PLACEHOLDER_ADDRESSES`]
+/// rather than a deployed address
+pub fn is_placeholder(address: &str) -> bool {
+    PLACEHOLDER_ADDRESSES.contains(&address)
+}
+
+/// Decode a base58check address carrying a 20-byte account behind one of `versions`
+fn base58check_account(address: &str, alphabet: &bs58::Alphabet, versions: &[u8]) -> Result<()> {
+    let bytes = bs58::decode(address)
+        .with_alphabet(alphabet)
+        .with_check(None)
+        .into_vec()
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    match bytes.split_first() {
+        Some((version, account)) if versions.contains(version) && account.len() == 20 => Ok(()),
+        Some((version, account)) => anyhow::bail!(
+            "unexpected version byte 0x{:02x} or length {} (expected 20 bytes behind {:02x?})",
+            version,
+            account.len(),
+            versions
+        ),
+        None => anyhow::bail!("address is empty"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders() {
+        assert!(is_placeholder("BridgeGov1111111111111111111111111111111111"));
+        assert!(is_placeholder("0x1234567890123456789012345678901234567890"));
+        assert!(is_placeholder("TBdTs1DEqhbGbTdoAmrpNtjzBm1FT7wnBs"));
+        assert!(is_placeholder("bc1q2eufqy352eufqy352eufqy352eufqy35c4n4ue"));
+        assert!(!is_placeholder("0xdAC17F958D2ee523a2206206994597C13D831ec7"));
+        assert!(!is_placeholder("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t"));
+        assert!(!is_placeholder("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"));
+        // Real addresses that merely look made-up, such as wrapped SOL
+        assert!(!is_placeholder("So11111111111111111111111111111111111111112"));
+        assert!(!is_placeholder("0x1234567890abcdef1234567890abcdef12345678"));
+    }
+
+    #[test]
+    fn test_evm_addresses() {
+        assert!(Evm.validate_token_address("0xdAC17F958D2ee523a2206206994597C13D831ec7").is_ok());
+        assert!(Evm.validate_token_address("dAC17F958D2ee523a2206206994597C13D831ec7").is_err());
+        assert!(Evm.validate_token_address("0xdAC17F958D2ee523a2206206994597C13D831e").is_err());
+        assert!(Evm.validate_token_address("0xgAC17F958D2ee523a2206206994597C13D831ec7").is_err());
+    }
+
+    #[test]
+    fn test_solana_addresses() {
+        assert!(Solana.validate_token_address("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").is_ok());
+        assert!(Solana.validate_token_address("11111111111111111111111111111111").is_ok());
+        assert!(Solana.validate_token_address("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGk").is_err());
+        assert!(Solana.validate_token_address("0PjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").is_err());
+    }
+
+    #[test]
+    fn test_tron_addresses() {
+        assert!(Tron.validate_token_address("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t").is_ok());
+        // One character changed breaks the checksum
+        assert!(Tron.validate_token_address("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6u").is_err());
+        // A valid Bitcoin address has the wrong version byte
+        assert!(Tron.validate_token_address("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa").is_err());
+    }
+
+    #[test]
+    fn test_bridge_existence_queries() {
+        let evm = Evm.bridge_existence_query("0xdAC17F958D2ee523a2206206994597C13D831ec7").unwrap();
+        assert_eq!(evm.method, "eth_getCode");
+        assert!((evm.exists)(&serde_json::json!("0x6080")));
+        assert!(!(evm.exists)(&serde_json::json!("0x")));
+        assert!(!(evm.exists)(&serde_json::json!("0x0")));
+
+        let tron = Tron.bridge_existence_query("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t").unwrap();
+        assert_eq!(tron.params[0], "0xa614f803b6fd780986a42c78ec9c7f77e6ded13c");
+
+        let solana = Solana.bridge_existence_query("11111111111111111111111111111111").unwrap();
+        assert!(!(solana.exists)(&serde_json::json!({ "context": { "slot": 1 }, "value": null })));
+
+        let xrp = Xrp.bridge_existence_query("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh").unwrap();
+        assert!(!(xrp.exists)(&serde_json::json!({ "error": "actNotFound", "status": "error" })));
+
+        assert!(Bitcoin.bridge_existence_query("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").is_none());
+    }
+
+    #[test]
+    fn test_xrp_addresses() {
+        assert!(Xrp.validate_token_address("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh").is_ok());
+        assert!(Xrp.validate_token_address("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTi").is_err());
+    }
+
+    #[test]
+    fn test_bitcoin_addresses() {
+        assert!(Bitcoin.validate_token_address("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").is_ok());
+        assert!(Bitcoin.validate_token_address("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa").is_ok());
+        assert!(Bitcoin.validate_token_address("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdr").is_err());
+        // Testnet
+        assert!(Bitcoin.validate_token_address("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx").is_err());
+        assert_eq!(Bitcoin.fetch_token_decimals("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"), Some(8));
+    }
+
+    #[test]
+    fn test_canonical_addresses() {
+        assert_eq!(
+            Evm.canonical_address("0xdAC17F958D2ee523a2206206994597C13D831ec7"),
+            "0xdac17f958d2ee523a2206206994597c13d831ec7"
+        );
+        assert_eq!(
+            Bitcoin.canonical_address("BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ"),
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+        );
+        assert_eq!(Bitcoin.canonical_address("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"), "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+        assert_eq!(Tron.canonical_address("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t"), "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t");
+    }
+
+    #[test]
+    fn test_confirmations() {
+        assert_eq!(describe_confirmations("bitcoin", 6), "6 blocks deep (~60 min)");
+        assert_eq!(describe_confirmations("xrp", 1), "1 validated ledger (~4 s)");
+        assert_eq!(describe_confirmations("ethereum", 12), "12 blocks");
+        assert_eq!(describe_confirmations("cardano", 30), "30 confirmations");
+
+        assert!(check_min_confirmations("bitcoin", 6).is_ok());
+        let err = check_min_confirmations("bitcoin", 3).unwrap_err();
+        assert_eq!(err.to_string(), "3 blocks deep (~30 min) is below the Bitcoin minimum of 6 blocks deep (~60 min)");
+        assert!(check_min_confirmations("xrp", 1).is_ok());
+        assert!(check_min_confirmations("cardano", 1).is_ok());
+    }
+
+    #[test]
+    fn test_registry() {
+        assert_eq!(adapter("polygon").unwrap().family(), "EVM");
+        assert_eq!(adapter("xrp").unwrap().family(), "XRP Ledger");
+        assert!(adapter("cardano").is_none());
+    }
+}
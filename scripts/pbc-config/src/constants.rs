@@ -0,0 +1,250 @@
+//! Bridge pallet constants read from runtime metadata, and checks of the
+//! config against the limits they impose.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use subxt::dynamic::{At, DecodedValue};
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::calls::BRIDGE_PALLET;
+use crate::{units, ChainConfig, Config};
+
+/// Every constant the bridge pallet exposes, decoded, by name
+pub fn bridge_constants(client: &OnlineClient<PolkadotConfig>) -> Result<BTreeMap<String, DecodedValue>> {
+    let metadata = client.metadata();
+    let pallet = metadata.pallet_by_name(BRIDGE_PALLET)
+        .context(format!("Runtime has no {} pallet", BRIDGE_PALLET))?;
+
+    pallet.constants()
+        .map(|constant| {
+            let address = subxt::dynamic::constant(BRIDGE_PALLET, constant.name());
+            let value = client.constants()
+                .at(&address)
+                .and_then(|thunk| thunk.to_value())
+                .context(format!("Failed to decode constant {}", constant.name()))?;
+            Ok((constant.name().to_string(), value))
+        })
+        .collect()
+}
+
+/// The runtime's hard limits relevant to authoring a config; `None` when
+/// the runtime doesn't declare that limit
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RuntimeLimits {
+    pub max_relayers: Option<u128>,
+    pub max_transfer_amount: Option<u128>,
+    pub min_confirmations: Option<u128>,
+    pub max_decimals: Option<u128>,
+}
+
+impl RuntimeLimits {
+    pub fn from_constants(constants: &BTreeMap<String, DecodedValue>) -> Self {
+        let number = |name: &str| constants.get(name).and_then(|value| value.as_u128());
+        Self {
+            max_relayers: number("MaxRelayers"),
+            max_transfer_amount: number("MaxTransferAmount"),
+            min_confirmations: number("MinConfirmations"),
+            max_decimals: number("MaxDecimals"),
+        }
+    }
+
+    /// Config values the runtime would reject
+    pub fn violations(&self, config: &Config) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        if let Some(max_relayers) = self.max_relayers {
+            if config.relayers.len() as u128 > max_relayers {
+                violations.push(format!(
+                    "{} relayers configured, runtime allows at most {}",
+                    config.relayers.len(), max_relayers
+                ));
+            }
+        }
+
+        if let Some(max_decimals) = self.max_decimals {
+            let mut chains: Vec<_> = config.chains.iter().collect();
+            chains.sort_by_key(|(chain, _)| *chain);
+            for (chain, chain_config) in chains {
+                violations.extend(decimals_violations(chain, chain_config, max_decimals));
+            }
+        }
+
+        if let Some(ceiling) = self.max_transfer_amount {
+            match units::parse_amount(&config.configuration.max_transfer_amount) {
+                Ok(amount) if amount > ceiling => violations.push(format!(
+                    "max_transfer_amount {} exceeds the runtime ceiling {}",
+                    amount, ceiling
+                )),
+                Ok(_) => {}
+                Err(e) => violations.push(format!("max_transfer_amount: {:#}", e)),
+            }
+        }
+
+        if let Some(min_confirmations) = self.min_confirmations {
+            let mut confirmation_blocks: Vec<_> = config.configuration.confirmation_blocks.iter().collect();
+            confirmation_blocks.sort_by_key(|(chain, _)| *chain);
+            for (chain, confirmations) in confirmation_blocks {
+                for (direction, blocks) in confirmations.directions() {
+                    if (blocks as u128) < min_confirmations {
+                        violations.push(format!(
+                            "confirmation_blocks{} for {} is {}, runtime enforces at least {}",
+                            direction, chain, blocks, min_confirmations
+                        ));
+                    }
+                }
+            }
+            if let Some(default) = config.configuration.default_confirmation_blocks {
+                for (direction, blocks) in default.directions() {
+                    if (blocks as u128) < min_confirmations {
+                        violations.push(format!(
+                            "default_confirmation_blocks{} is {}, runtime enforces at least {}",
+                            direction, blocks, min_confirmations
+                        ));
+                    }
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+/// The bridge pallet's `MaxDecimals`, if the runtime declares one
+pub fn max_decimals(client: &OnlineClient<PolkadotConfig>) -> Option<u128> {
+    client.constants()
+        .at(&subxt::dynamic::constant(BRIDGE_PALLET, "MaxDecimals"))
+        .and_then(|thunk| thunk.to_value())
+        .ok()
+        .and_then(|value| value.as_u128())
+}
+
+/// `chain`'s decimals above the runtime's `max_decimals`
+pub fn decimals_violations(chain: &str, chain_config: &ChainConfig, max_decimals: u128) -> Vec<String> {
+    [("source_decimals", chain_config.source_decimals), ("dest_decimals", chain_config.dest_decimals())]
+        .into_iter()
+        .filter(|(_, decimals)| *decimals as u128 > max_decimals)
+        .map(|(side, decimals)| format!(
+            "{} for {} is {}, runtime allows at most {}",
+            side, chain, decimals, max_decimals
+        ))
+        .collect()
+}
+
+/// How large a single normal-class extrinsic may be, from the `System`
+/// pallet's `BlockLength` and `BlockWeights`; `None` when the runtime doesn't say
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExtrinsicLimits {
+    /// Encoded length in bytes
+    pub max_length: Option<u64>,
+    /// Ref-time weight
+    pub max_ref_time: Option<u64>,
+}
+
+impl ExtrinsicLimits {
+    pub fn read(client: &OnlineClient<PolkadotConfig>) -> Self {
+        let system_constant = |name: &str| {
+            client.constants()
+                .at(&subxt::dynamic::constant("System", name))
+                .and_then(|thunk| thunk.to_value())
+                .ok()
+        };
+        Self::from_values(system_constant("BlockLength").as_ref(), system_constant("BlockWeights").as_ref())
+    }
+
+    fn from_values(block_length: Option<&DecodedValue>, block_weights: Option<&DecodedValue>) -> Self {
+        let max_length = block_length
+            .and_then(|length| length.at("max").at("normal").and_then(|max| max.as_u128()));
+        // `max_extrinsic` is an `Option<Weight>`; without it only the block's own maximum bounds us
+        let ref_time = |weight: &DecodedValue| weight.at("ref_time").and_then(|ref_time| ref_time.as_u128());
+        let max_ref_time = block_weights.and_then(|weights| {
+            weights.at("per_class").at("normal").at("max_extrinsic").at(0usize).and_then(ref_time)
+                .or_else(|| weights.at("max_block").and_then(ref_time))
+        });
+        Self {
+            max_length: max_length.and_then(|max| u64::try_from(max).ok()),
+            max_ref_time: max_ref_time.and_then(|max| u64::try_from(max).ok()),
+        }
+    }
+
+    /// The factor an extrinsic of `length` bytes and `ref_time` weight could
+    /// grow by and still fit; below 1 it would be rejected
+    pub fn headroom(&self, length: u64, ref_time: Option<u64>) -> f64 {
+        let ratio = |max: Option<u64>, used: Option<u64>| match (max, used) {
+            (Some(max), Some(used)) if used > 0 => max as f64 / used as f64,
+            _ => f64::INFINITY,
+        };
+        ratio(self.max_length, Some(length)).min(ratio(self.max_ref_time, ref_time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subxt::dynamic::Value;
+
+    #[test]
+    fn test_runtime_limit_violations() {
+        let constants: BTreeMap<String, DecodedValue> = [
+            ("MaxRelayers", 1u128),
+            ("MaxTransferAmount", 1_000_000),
+            ("MinConfirmations", 6),
+            ("MaxDecimals", 18),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), Value::u128(value).map_context(|_| 0u32)))
+        .collect();
+        let limits = RuntimeLimits::from_constants(&constants);
+        assert_eq!(limits.min_confirmations, Some(6));
+
+        let mut config = crate::test_config::config(serde_json::json!({
+            "solana": crate::test_config::chain(1, "So11111111111111111111111111111111111111112", "SoBridge", 24),
+        }), serde_json::json!({
+            "ethereum": 12,
+            "tron": 3,
+            "xrp": { "deposit": 6, "withdrawal": 2 },
+        }));
+        config["relayers"] = serde_json::json!([
+            "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty",
+            "5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y",
+        ]);
+        let config: Config = serde_json::from_value(config).unwrap();
+
+        let violations = limits.violations(&config);
+        assert_eq!(violations.len(), 6, "{:?}", violations);
+        assert_eq!(violations[1], "source_decimals for solana is 24, runtime allows at most 18");
+        assert_eq!(violations[2], "dest_decimals for solana is 24, runtime allows at most 18");
+        assert!(violations[4].contains("tron"));
+        assert!(violations[5].starts_with("confirmation_blocks.withdrawal for xrp is 2"), "{}", violations[5]);
+        assert!(RuntimeLimits::default().violations(&config).is_empty());
+    }
+
+    #[test]
+    fn test_extrinsic_limits() {
+        let per_class = |max_extrinsic: Value| Value::named_composite([
+            ("normal", Value::named_composite([("max_extrinsic", max_extrinsic)])),
+        ]);
+        let weight = |ref_time: u128| Value::named_composite([("ref_time", Value::u128(ref_time)), ("proof_size", Value::u128(0))]);
+        let block_length = Value::named_composite([("max", Value::named_composite([("normal", Value::u128(3_932_160))]))])
+            .map_context(|_| 0u32);
+        let block_weights = Value::named_composite([
+            ("max_block", weight(2_000_000_000_000)),
+            ("per_class", per_class(Value::unnamed_variant("Some", [weight(1_479_000_000_000)]))),
+        ])
+        .map_context(|_| 0u32);
+
+        let limits = ExtrinsicLimits::from_values(Some(&block_length), Some(&block_weights));
+        assert_eq!(limits, ExtrinsicLimits { max_length: Some(3_932_160), max_ref_time: Some(1_479_000_000_000) });
+        assert_eq!(limits.headroom(3_932_160 / 4, None), 4.0);
+        assert_eq!(limits.headroom(1_000, Some(1_479_000_000_000 * 2)), 0.5);
+
+        let no_extrinsic_max = Value::named_composite([
+            ("max_block", weight(2_000_000_000_000)),
+            ("per_class", per_class(Value::unnamed_variant("None", []))),
+        ])
+        .map_context(|_| 0u32);
+        let limits = ExtrinsicLimits::from_values(None, Some(&no_extrinsic_max));
+        assert_eq!(limits.max_ref_time, Some(2_000_000_000_000));
+        assert_eq!(limits.max_length, None);
+        assert!(ExtrinsicLimits::default().headroom(u64::MAX, Some(u64::MAX)).is_infinite());
+    }
+}
@@ -0,0 +1,58 @@
+//! `--output csv` for tabular commands, for importing into spreadsheets.
+//!
+//! Amounts are written in raw base units with a separate human-readable
+//! column, so nothing is lost to a spreadsheet's float parsing.
+
+use anyhow::{Context, Result};
+
+/// Render `rows` under `header` as CSV, quoting fields where needed
+pub fn render(header: &[&str], rows: impl IntoIterator<Item = Vec<String>>) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(header).context("Failed to write CSV header")?;
+    for row in rows {
+        writer.write_record(&row).context("Failed to write CSV row")?;
+    }
+    let bytes = writer.into_inner().context("Failed to flush CSV output")?;
+    String::from_utf8(bytes).context("CSV output is not valid UTF-8")
+}
+
+/// Print `rows` under `header` as CSV on stdout
+pub fn print(header: &[&str], rows: impl IntoIterator<Item = Vec<String>>) -> Result<()> {
+    print!("{}", render(header, rows)?);
+    Ok(())
+}
+
+/// A JSON value as a CSV cell: strings unquoted, null and missing empty
+pub fn cell(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(value)) => value.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_quotes_fields() {
+        let rows = vec![
+            vec!["ethereum".to_string(), "1000".to_string()],
+            vec!["a,b".to_string(), "say \"hi\"".to_string()],
+        ];
+        assert_eq!(
+            render(&["chain", "locked"], rows).unwrap(),
+            "chain,locked\nethereum,1000\n\"a,b\",\"say \"\"hi\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_cell() {
+        let value = serde_json::json!({ "rate": "10", "decimals": 18, "fee": null });
+        assert_eq!(cell(value.get("rate")), "10");
+        assert_eq!(cell(value.get("decimals")), "18");
+        assert_eq!(cell(value.get("fee")), "");
+        assert_eq!(cell(value.get("missing")), "");
+    }
+}
@@ -0,0 +1,297 @@
+//! Scanning finalized blocks for bridge pallet events.
+
+use anyhow::{Context, Result};
+use parity_scale_codec::{Compact, CompactLen, Decode};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+use subxt::backend::legacy::rpc_methods::NumberOrHex;
+use subxt::backend::legacy::LegacyRpcMethods;
+use subxt::config::Header;
+use subxt::events::Phase;
+use subxt::{OnlineClient, PolkadotConfig};
+use tracing::warn;
+
+use crate::calls::BRIDGE_PALLET;
+
+/// A bridge pallet event found while scanning a block range
+#[derive(Debug, Serialize)]
+pub struct BridgeEvent {
+    pub block_number: u64,
+    /// `None` for events emitted outside an extrinsic (initialization/finalization)
+    pub extrinsic_index: Option<u32>,
+    pub event: String,
+    pub fields: serde_json::Value,
+}
+
+impl std::fmt::Display for BridgeEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let extrinsic = match self.extrinsic_index {
+            Some(index) => index.to_string(),
+            None => "-".to_string(),
+        };
+        write!(f, "#{} ext {} {}.{} {}", self.block_number, extrinsic, BRIDGE_PALLET, self.event, self.fields)
+    }
+}
+
+/// Collect bridge events from finalized blocks `from..=to`, optionally only those named `event_filter`
+pub async fn scan(
+    client: &OnlineClient<PolkadotConfig>,
+    rpc: &LegacyRpcMethods<PolkadotConfig>,
+    from: u64,
+    to: u64,
+    event_filter: Option<&str>,
+) -> Result<Vec<BridgeEvent>> {
+    let mut found = Vec::new();
+    scan_each(client, rpc, from, to, event_filter, |event| {
+        found.push(event);
+        Ok(())
+    }).await?;
+    Ok(found)
+}
+
+/// Like [`scan`], but hand each event to `on_event` as soon as its block is
+/// decoded instead of buffering the whole range. Returns the number of events.
+pub async fn scan_each(
+    client: &OnlineClient<PolkadotConfig>,
+    rpc: &LegacyRpcMethods<PolkadotConfig>,
+    from: u64,
+    to: u64,
+    event_filter: Option<&str>,
+    mut on_event: impl FnMut(BridgeEvent) -> Result<()>,
+) -> Result<usize> {
+    if from > to {
+        anyhow::bail!("--from ({}) must not be greater than --to ({})", from, to);
+    }
+
+    let finalized_hash = rpc.chain_get_finalized_head().await
+        .context("Failed to get finalized head")?;
+    let finalized_number = rpc.chain_get_header(Some(finalized_hash)).await
+        .context("Failed to get finalized header")?
+        .context("Finalized header not found")?
+        .number() as u64;
+    if to > finalized_number {
+        anyhow::bail!("--to ({}) is beyond the latest finalized block ({})", to, finalized_number);
+    }
+
+    let bridge_pallet_index = client.metadata().pallet_by_name(BRIDGE_PALLET).map(|pallet| pallet.index());
+
+    let mut found = 0;
+    for block_number in from..=to {
+        let block_hash = rpc.chain_get_block_hash(Some(NumberOrHex::Number(block_number))).await
+            .context(format!("Failed to get hash of block {}", block_number))?
+            .context(format!("Block {} not found", block_number))?;
+
+        let events = client.blocks()
+            .at(block_hash)
+            .await
+            .context(format!("Failed to get block {}", block_number))?
+            .events()
+            .await
+            .context(format!("Failed to get events for block {}", block_number))?;
+
+        // Bytes of every event decoded so far, to locate the raw bytes of one that isn't
+        let mut decoded_len = 0;
+        for event in events.iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    // Without the event's layout we can't find where the next one starts,
+                    // so report what we can and move on to the next block.
+                    warn!("Block {}: undecodable event, skipping the rest of the block: {}", block_number, e);
+                    let events_key = client.storage()
+                        .address_bytes(&subxt::dynamic::storage("System", "Events", Vec::<subxt::dynamic::Value>::new()))?;
+                    let raw = client.storage().at(block_hash)
+                        .fetch_raw(events_key)
+                        .await
+                        .context(format!("Failed to fetch raw events for block {}", block_number))?;
+                    if let Some(undecoded) = raw.as_deref().and_then(|raw| UndecodedEvent::at(raw, decoded_len)) {
+                        warn!(
+                            "Block {}: raw event pallet #{} variant #{}: {}",
+                            block_number, undecoded.pallet_index, undecoded.variant_index, undecoded.bytes_hex
+                        );
+                        if Some(undecoded.pallet_index) == bridge_pallet_index {
+                            on_event(undecoded.into_bridge_event(block_number))?;
+                            found += 1;
+                        }
+                    }
+                    break;
+                }
+            };
+            decoded_len += event.bytes().len();
+
+            if event.pallet_name() != BRIDGE_PALLET {
+                continue;
+            }
+            if event_filter.is_some_and(|name| name != event.variant_name()) {
+                continue;
+            }
+
+            let extrinsic_index = match event.phase() {
+                Phase::ApplyExtrinsic(index) => Some(index),
+                Phase::Initialization | Phase::Finalization => None,
+            };
+            let fields = match event.field_values() {
+                Ok(values) => serde_json::to_value(values)?,
+                Err(e) => {
+                    warn!(
+                        "Block {}: could not decode fields of {}.{}, showing raw bytes: {}",
+                        block_number, BRIDGE_PALLET, event.variant_name(), e
+                    );
+                    serde_json::json!({ "raw": format!("0x{}", hex::encode(event.field_bytes())) })
+                }
+            };
+
+            on_event(BridgeEvent {
+                block_number,
+                extrinsic_index,
+                event: event.variant_name().to_string(),
+                fields,
+            })?;
+            found += 1;
+        }
+    }
+
+    Ok(found)
+}
+
+/// Blocks behind the last processed one whose events are remembered for deduplication
+const DEDUP_WINDOW: u64 = 64;
+
+/// How far a long-running scan of finalized blocks has got, so that after a
+/// reconnect it resumes from the last processed block instead of skipping or
+/// re-reporting blocks finalized while it was disconnected
+#[derive(Debug, Default)]
+pub struct Resume {
+    last_block: Option<u64>,
+    /// (block, extrinsic index) of events already reported in recent blocks
+    seen: BTreeSet<(u64, Option<u32>)>,
+}
+
+impl Resume {
+    /// Blocks left to scan now that `head` is finalized: everything after the
+    /// last processed block, or just `head` when nothing was processed yet
+    pub fn pending(&self, head: u64) -> Option<RangeInclusive<u64>> {
+        let from = self.last_block.map_or(head, |last| last + 1);
+        (from <= head).then_some(from..=head)
+    }
+
+    /// Mark every block up to `head` as processed, returning the events of
+    /// `found` whose (block, extrinsic index) wasn't reported before
+    pub fn advance(&mut self, head: u64, found: Vec<BridgeEvent>) -> Vec<BridgeEvent> {
+        let keys: Vec<_> = found.iter().map(|event| (event.block_number, event.extrinsic_index)).collect();
+        let fresh = found
+            .into_iter()
+            .filter(|event| !self.seen.contains(&(event.block_number, event.extrinsic_index)))
+            .collect();
+        self.seen.extend(keys);
+
+        let last = self.last_block.map_or(head, |last| last.max(head));
+        self.last_block = Some(last);
+        self.seen = self.seen.split_off(&(last.saturating_sub(DEDUP_WINDOW), None));
+        fresh
+    }
+}
+
+/// The first event subxt couldn't decode, located in the raw `System::Events` bytes
+#[derive(Debug, PartialEq, Eq)]
+struct UndecodedEvent {
+    extrinsic_index: Option<u32>,
+    pallet_index: u8,
+    variant_index: u8,
+    /// Fields and topics of this event plus any events after it; their boundaries are unknown
+    bytes_hex: String,
+}
+
+impl UndecodedEvent {
+    /// Parse the event starting `decoded_len` bytes into the event records of `raw`
+    fn at(raw: &[u8], decoded_len: usize) -> Option<Self> {
+        let count = Compact::<u32>::decode(&mut &raw[..]).ok()?;
+        let mut rest = raw.get(Compact::<u32>::compact_len(&count.0) + decoded_len..)?;
+
+        let extrinsic_index = match rest.first()? {
+            0 => Some(u32::from_le_bytes(rest.get(1..5)?.try_into().ok()?)),
+            1 | 2 => None,
+            _ => return None,
+        };
+        rest = &rest[if extrinsic_index.is_some() { 5 } else { 1 }..];
+
+        Some(Self {
+            extrinsic_index,
+            pallet_index: *rest.first()?,
+            variant_index: *rest.get(1)?,
+            bytes_hex: format!("0x{}", hex::encode(&rest[2..])),
+        })
+    }
+
+    fn into_bridge_event(self, block_number: u64) -> BridgeEvent {
+        BridgeEvent {
+            block_number,
+            extrinsic_index: self.extrinsic_index,
+            event: format!("<variant #{}>", self.variant_index),
+            fields: serde_json::json!({ "raw": self.bytes_hex }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parity_scale_codec::Encode;
+
+    #[test]
+    fn test_locate_undecoded_event() {
+        // Two events: a 4-byte one decoded fine, then ApplyExtrinsic(3) pallet 40 variant 9
+        let mut raw = Compact(2u32).encode();
+        raw.extend([1, 0, 0, 0]);
+        raw.extend([0, 3, 0, 0, 0, 40, 9, 0xde, 0xad, 0]);
+
+        let undecoded = UndecodedEvent::at(&raw, 4).unwrap();
+        assert_eq!(undecoded, UndecodedEvent {
+            extrinsic_index: Some(3),
+            pallet_index: 40,
+            variant_index: 9,
+            bytes_hex: "0xdead00".to_string(),
+        });
+        assert_eq!(undecoded.into_bridge_event(7).event, "<variant #9>");
+
+        assert!(UndecodedEvent::at(&raw, 100).is_none());
+    }
+
+    #[test]
+    fn test_resume_after_gap() {
+        let event = |block_number, extrinsic_index| BridgeEvent {
+            block_number,
+            extrinsic_index,
+            event: "TokenMappingSet".to_string(),
+            fields: serde_json::Value::Null,
+        };
+
+        let mut resume = Resume::default();
+        assert_eq!(resume.pending(100), Some(100..=100));
+        assert_eq!(resume.advance(100, vec![event(100, Some(1)), event(100, Some(1))]).len(), 2);
+
+        // Disconnected while 101..=104 finalized
+        assert_eq!(resume.pending(105), Some(101..=105));
+        assert_eq!(resume.advance(105, vec![event(103, None)]).len(), 1);
+
+        // A lagging node re-announcing old blocks is ignored, and a rescan isn't re-reported
+        assert_eq!(resume.pending(104), None);
+        assert!(resume.advance(105, vec![event(100, Some(1)), event(103, None)]).is_empty());
+        assert_eq!(resume.advance(105, vec![event(105, Some(2))]).len(), 1);
+    }
+
+    #[test]
+    fn test_display_bridge_event() {
+        let event = BridgeEvent {
+            block_number: 1200,
+            extrinsic_index: Some(2),
+            event: "TokenMappingSet".to_string(),
+            fields: serde_json::json!({ "decimals": 18 }),
+        };
+        assert_eq!(event.to_string(), "#1200 ext 2 Bridge.TokenMappingSet {\"decimals\":18}");
+
+        let event = BridgeEvent { extrinsic_index: None, ..event };
+        assert!(event.to_string().starts_with("#1200 ext - "));
+    }
+}
@@ -0,0 +1,124 @@
+//! Plain-English descriptions of what configuring a chain would do, for
+//! change reviews where not every approver reads call data.
+
+use anyhow::{Context, Result};
+use subxt::utils::AccountId32;
+
+use crate::plan::InitStep;
+use crate::{chains, units, BridgeConfiguration, ChainConfig, ConfirmationBlocks, RateSource};
+
+/// What configuring `chain` sets, as a few sentences
+pub fn describe(
+    chain: &str,
+    chain_config: &ChainConfig,
+    bridge_config: &BridgeConfiguration,
+    relayer_weights: &[(AccountId32, u32)],
+) -> Result<String> {
+    let rate = chain_config.runtime_exchange_rate()
+        .context(format!("Invalid exchange rate for {}", chain))?;
+    let fee_permill = units::percent_to_permill(&bridge_config.bridge_fee_percent)
+        .context("Invalid bridge_fee_percent")?;
+    let confirmations = match bridge_config.confirmations_for(chain)? {
+        ConfirmationBlocks::Both(blocks) => format!("{} of confirmation", chains::describe_confirmations(chain, blocks)),
+        ConfirmationBlocks::PerDirection { deposit, withdrawal } => format!(
+            "{} of confirmation for deposits and {} for withdrawals",
+            chains::describe_confirmations(chain, deposit),
+            chains::describe_confirmations(chain, withdrawal)
+        ),
+    };
+
+    let token = match &chain_config.token_symbol {
+        Some(symbol) => format!("{} ({})", symbol, chain_config.token_address),
+        None => chain_config.token_address.clone(),
+    };
+    let mut sentences = vec![format!(
+        "Will map {} token {} at rate 1 ETRID = {} units with {} and fee {}.",
+        chain,
+        token,
+        rate,
+        confirmations,
+        units::permill_to_percent(fee_permill)
+    )];
+    if chain_config.rate_source == RateSource::Oracle {
+        sentences.push("The rate comes from the oracle when configuring, falling back to exchange_rate.".to_string());
+    }
+    let dest_decimals = chain_config.dest_decimals();
+    if chain_config.source_decimals == dest_decimals {
+        sentences.push(format!("Amounts keep {} decimals on both sides.", dest_decimals));
+    } else {
+        sentences.push(format!(
+            "Amounts are scaled from {} decimals on {} to {} on Etrid.",
+            chain_config.source_decimals, chain, dest_decimals
+        ));
+    }
+    sentences.push(format!("1 {} token becomes {} ETRID.", chain, chain_config.scaling_factor()?));
+    if !relayer_weights.is_empty() {
+        let weights: Vec<String> = relayer_weights.iter()
+            .map(|(relayer, weight)| format!("{} to {}", relayer, weight))
+            .collect();
+        sentences.push(format!("Will set relayer weights: {}.", weights.join(", ")));
+    }
+
+    Ok(sentences.join(" "))
+}
+
+/// What the steps still needed on-chain change, one sentence per field
+pub fn describe_changes(steps: &[InitStep]) -> String {
+    if steps.is_empty() {
+        return "On-chain state already matches, so configuring would change nothing.".to_string();
+    }
+
+    let mut sentences = vec!["Compared with on-chain state:".to_string()];
+    for change in steps.iter().flat_map(|step| &step.changes) {
+        sentences.push(match &change.current {
+            Some(current) => format!("- {} changes from {} to {}.", change.field, current, change.desired),
+            None => format!("- {} is set to {} (currently unset).", change.field, change.desired),
+        });
+    }
+    sentences.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::FieldChange;
+    use crate::Config;
+
+    #[test]
+    fn test_describe() {
+        let mut tron = crate::test_config::chain(1, "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t", "TBridge", 6);
+        tron["exchange_rate"] = serde_json::json!("2000");
+        tron["token_symbol"] = serde_json::json!("USDT");
+        let config: Config = serde_json::from_value(crate::test_config::config(
+            serde_json::json!({ "tron": tron }),
+            serde_json::json!({ "tron": { "deposit": 20, "withdrawal": 12 } }),
+        ))
+        .unwrap();
+
+        let text = describe("tron", &config.chains["tron"], &config.configuration, &[]).unwrap();
+        assert_eq!(
+            text,
+            "Will map tron token USDT (TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t) at rate 1 ETRID = 2000 units \
+             with 20 blocks of confirmation for deposits and 12 blocks for withdrawals and fee 0.1%. \
+             Amounts keep 6 decimals on both sides. \
+             1 tron token becomes 0.0005 ETRID."
+        );
+    }
+
+    #[test]
+    fn test_describe_changes() {
+        assert!(describe_changes(&[]).contains("change nothing"));
+
+        let step = InitStep {
+            call: "set_fee".to_string(),
+            changes: vec![
+                FieldChange { field: "fee".to_string(), current: Some("0.1%".to_string()), desired: "0.3%".to_string() },
+                FieldChange { field: "confirmations".to_string(), current: None, desired: "12".to_string() },
+            ],
+        };
+        assert_eq!(
+            describe_changes(&[step]),
+            "Compared with on-chain state:\n- fee changes from 0.1% to 0.3%.\n- confirmations is set to 12 (currently unset)."
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Waiting for extrinsic inclusion or finalization with progress heartbeats.
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::time::Duration;
+use subxt::backend::legacy::LegacyRpcMethods;
+use subxt::blocks::ExtrinsicEvents;
+use subxt::config::Header;
+use subxt::dynamic::At;
+use subxt::error::DispatchError;
+use subxt::tx::{TxProgress, TxStatus};
+use subxt::{Metadata, OnlineClient, PolkadotConfig};
+use tracing::{debug, info, warn};
+
+/// How often to report finalization progress while waiting
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often `--reorg-guard-blocks` checks the best block while guarding
+pub const REORG_GUARD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The best block that included an extrinsic was retracted before
+/// `--reorg-guard-blocks` more blocks were built on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Orphaned {
+    pub block_number: u64,
+}
+
+impl fmt::Display for Orphaned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "block #{} that included the extrinsic was orphaned by a reorg", self.block_number)
+    }
+}
+
+impl std::error::Error for Orphaned {}
+
+/// A submitted extrinsic didn't reach `--confirmation` within
+/// `--finality-timeout`; it may still be applied later
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut {
+    pub confirmation: Confirmation,
+    pub timeout: Duration,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Extrinsic was not {} within {}s (--finality-timeout)",
+            match self.confirmation {
+                Confirmation::InBlock => "included in a block",
+                Confirmation::Finalized => "finalized",
+            },
+            self.timeout.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// How far a submitted extrinsic must get before we treat it as applied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Confirmation {
+    /// Included in a best block; fast, but the block can still be retracted
+    InBlock,
+    /// Included in a finalized block
+    #[default]
+    Finalized,
+}
+
+/// Wait for `progress` to reach `confirmation`, logging the finalized
+/// height against the inclusion block every [`HEARTBEAT_INTERVAL`].
+///
+/// With [`Confirmation::InBlock`] and a non-zero `guard_blocks`, an
+/// inclusion only counts once the best chain is `guard_blocks` past it and
+/// still has it; if it is retracted first this fails with [`Orphaned`].
+/// With `follow_orphaned` it instead keeps following the extrinsic, which
+/// the pool normally re-includes, and only fails with [`Orphaned`] if the
+/// pool then drops it or finds it invalid.
+///
+/// Callers bound this with `--finality-timeout`; dropping the future on
+/// timeout also stops the heartbeat.
+pub async fn wait_for_confirmation(
+    mut progress: TxProgress<PolkadotConfig, OnlineClient<PolkadotConfig>>,
+    rpc: &LegacyRpcMethods<PolkadotConfig>,
+    metadata: Metadata,
+    target: &str,
+    confirmation: Confirmation,
+    guard_blocks: u32,
+    follow_orphaned: bool,
+) -> Result<ExtrinsicEvents<PolkadotConfig>> {
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    // The first tick completes immediately; skip it so we only log once waiting is noticeable
+    heartbeat.tick().await;
+    let mut guard_poll = tokio::time::interval(REORG_GUARD_POLL_INTERVAL);
+    let mut included_in: Option<u64> = None;
+    // An in-block inclusion waiting out `guard_blocks`: its number, hash and events
+    let mut guarded: Option<(u64, subxt::utils::H256, ExtrinsicEvents<PolkadotConfig>)> = None;
+    // The last guarded inclusion a reorg orphaned, while following the extrinsic past it
+    let mut orphaned: Option<Orphaned> = None;
+
+    loop {
+        tokio::select! {
+            status = progress.next() => {
+                let status = status
+                    .context("Transaction status stream ended before finalization")?
+                    .context("Failed to watch extrinsic")?;
+                match status {
+                    TxStatus::InBestBlock(in_block) if confirmation == Confirmation::InBlock => {
+                        let events = in_block.fetch_events().await
+                            .context("Failed to fetch extrinsic events")?;
+                        check_success(&events, &metadata, target)?;
+                        if guard_blocks == 0 {
+                            return Ok(events);
+                        }
+                        let number = block_number(rpc, in_block.block_hash()).await?;
+                        info!("{}: included in #{}, waiting {} more blocks before trusting it", target, number, guard_blocks);
+                        guarded = Some((number, in_block.block_hash(), events));
+                    }
+                    TxStatus::InBestBlock(in_block) => {
+                        included_in = block_number(rpc, in_block.block_hash()).await.ok();
+                        debug!("{}: included in best block {:?}", target, included_in);
+                    }
+                    TxStatus::NoLongerInBestBlock => {
+                        if let Some((block_number, ..)) = guarded.take() {
+                            orphaned = Some(orphan(target, Orphaned { block_number }, follow_orphaned)?);
+                            continue;
+                        }
+                        warn!("{}: extrinsic was retracted from the best chain, waiting for re-inclusion", target);
+                        included_in = None;
+                    }
+                    TxStatus::InFinalizedBlock(in_block) => {
+                        let events = in_block.fetch_events().await
+                            .context("Failed to fetch extrinsic events")?;
+                        check_success(&events, &metadata, target)?;
+                        return Ok(events);
+                    }
+                    TxStatus::Error { message } => anyhow::bail!("Extrinsic error: {}", message),
+                    TxStatus::Invalid { message } | TxStatus::Dropped { message } if orphaned.is_some() => {
+                        let orphaned = orphaned.expect("checked by the guard");
+                        return Err(anyhow::Error::new(orphaned)
+                            .context(format!("Extrinsic was not re-included after the reorg: {}", message)));
+                    }
+                    TxStatus::Invalid { message } => anyhow::bail!("Extrinsic invalid: {}", message),
+                    TxStatus::Dropped { message } => anyhow::bail!("Extrinsic dropped: {}", message),
+                    TxStatus::Validated | TxStatus::Broadcasted { .. } => {}
+                }
+            }
+            _ = guard_poll.tick(), if guarded.is_some() => {
+                let Some((number, hash, _)) = &guarded else { continue };
+                let (number, hash) = (*number, *hash);
+                let best = best_number(rpc).await?;
+                if best < number + u64::from(guard_blocks) {
+                    continue;
+                }
+                let canonical = rpc.chain_get_block_hash(Some(number.into())).await
+                    .context("Failed to get block hash")?;
+                if canonical != Some(hash) {
+                    guarded = None;
+                    orphaned = Some(orphan(target, Orphaned { block_number: number }, follow_orphaned)?);
+                    continue;
+                }
+                debug!("{}: #{} is still canonical at best #{}", target, number, best);
+                let (_, _, events) = guarded.take().expect("guarded inclusion checked above");
+                return Ok(events);
+            }
+            _ = heartbeat.tick() => {
+                let finalized = finalized_number(rpc).await
+                    .map_or_else(|_| "unknown".to_string(), |number| format!("#{}", number));
+                match (&guarded, included_in) {
+                    (Some((block, ..)), _) => info!(
+                        "{}: guarding #{} against reorgs for {} blocks, finalized {}",
+                        target, block, guard_blocks, finalized
+                    ),
+                    (None, Some(block)) => info!("{}: waiting for finalization, finalized {} / included in #{}", target, finalized, block),
+                    (None, None) => info!("{}: waiting for inclusion, finalized {}", target, finalized),
+                }
+            }
+        }
+    }
+}
+
+/// Wait until the finalized block `block_hash` is `depth` blocks behind the
+/// latest finalized head, logging progress every [`HEARTBEAT_INTERVAL`], and
+/// return the depth reached
+pub async fn wait_for_depth(
+    rpc: &LegacyRpcMethods<PolkadotConfig>,
+    target: &str,
+    block_hash: subxt::utils::H256,
+    depth: u32,
+) -> Result<u64> {
+    let included = block_number(rpc, block_hash).await?;
+    let mut poll = tokio::time::interval(HEARTBEAT_INTERVAL);
+    loop {
+        poll.tick().await;
+        let finalized = finalized_number(rpc).await?;
+        let reached = finalized.saturating_sub(included);
+        if reached >= u64::from(depth) {
+            info!("{}: #{} is {} blocks behind finalized #{}", target, included, reached, finalized);
+            return Ok(reached);
+        }
+        info!("{}: #{} is {} of {} blocks deep, finalized #{}", target, included, reached, depth, finalized);
+    }
+}
+
+/// Report a guarded inclusion the reorg orphaned: an error, or with
+/// `follow_orphaned` the orphaning to remember while we wait for re-inclusion
+fn orphan(target: &str, orphaned: Orphaned, follow_orphaned: bool) -> Result<Orphaned> {
+    warn!("{}: {}", target, orphaned);
+    if !follow_orphaned {
+        return Err(orphaned.into());
+    }
+    info!("{}: still following the extrinsic, the pool should re-include it", target);
+    Ok(orphaned)
+}
+
+/// Fail if the extrinsic emitted `System.ExtrinsicFailed`.
+///
+/// Events are concatenated without lengths, so one we can't decode (e.g. a
+/// newer runtime than our metadata) hides every event after it, including a
+/// possible `ExtrinsicFailed`. The outcome is then unknown and reported as an
+/// error rather than assumed to be success.
+fn check_success(events: &ExtrinsicEvents<PolkadotConfig>, metadata: &Metadata, target: &str) -> Result<()> {
+    for event in events.iter() {
+        let event = event.map_err(|e| anyhow::anyhow!(
+            "{}: could not decode every event ({}); the extrinsic's outcome is unknown, check it on-chain",
+            target, e
+        ))?;
+        if event.pallet_name() == "System" && event.variant_name() == "ExtrinsicFailed" {
+            let error = DispatchError::decode_from(event.field_bytes(), metadata.clone())
+                .context("Extrinsic failed with an undecodable dispatch error")?;
+            anyhow::bail!("Extrinsic failed: {}", error);
+        }
+    }
+    Ok(())
+}
+
+pub async fn block_number(rpc: &LegacyRpcMethods<PolkadotConfig>, hash: subxt::utils::H256) -> Result<u64> {
+    let header = rpc.chain_get_header(Some(hash)).await
+        .context("Failed to get block header")?
+        .context("Block header not found")?;
+    Ok(header.number() as u64)
+}
+
+/// Fee the signer paid, from the extrinsic's `TransactionPayment.TransactionFeePaid` event
+pub fn fee_paid(events: &ExtrinsicEvents<PolkadotConfig>) -> Option<u128> {
+    events.iter()
+        .map_while(|event| event.ok())
+        .find(|event| event.pallet_name() == "TransactionPayment" && event.variant_name() == "TransactionFeePaid")
+        .and_then(|event| event.field_values().ok())
+        .and_then(|fields| fields.at("actual_fee").and_then(|fee| fee.as_u128()))
+}
+
+/// Number of the current best block
+pub async fn best_number(rpc: &LegacyRpcMethods<PolkadotConfig>) -> Result<u64> {
+    let header = rpc.chain_get_header(None).await
+        .context("Failed to get best header")?
+        .context("Best header not found")?;
+    Ok(header.number() as u64)
+}
+
+/// Number of the latest finalized block
+pub async fn finalized_number(rpc: &LegacyRpcMethods<PolkadotConfig>) -> Result<u64> {
+    let hash = rpc.chain_get_finalized_head().await
+        .context("Failed to get finalized head")?;
+    block_number(rpc, hash).await
+}
@@ -0,0 +1,118 @@
+//! Checking that signers can pay for a whole run before anything is
+//! submitted, rather than running dry partway through.
+
+use anyhow::{Context, Result};
+use subxt::backend::legacy::LegacyRpcMethods;
+use subxt::dynamic::{At, Value};
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::units;
+
+/// What one signer must hold to pay for its share of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Funding {
+    /// Estimated fees of every call the signer will submit
+    pub fees: u128,
+    /// Extra on top of `fees`, in percent, for estimates that come in low
+    pub buffer_percent: u32,
+    /// Left over so the account isn't reaped by the last call
+    pub existential_deposit: u128,
+}
+
+impl Funding {
+    /// Fees, plus the buffer, plus the existential deposit
+    pub fn required(&self) -> u128 {
+        let buffer = self.fees.saturating_mul(self.buffer_percent as u128) / 100;
+        self.fees.saturating_add(buffer).saturating_add(self.existential_deposit)
+    }
+
+    /// How much more than `free` the run needs, if `free` isn't enough
+    pub fn shortfall(&self, free: u128) -> Option<u128> {
+        self.required().checked_sub(free).filter(|shortfall| *shortfall > 0)
+    }
+}
+
+/// The native token's symbol and decimals from `system_properties`, for
+/// printing balances; base units of `ETRID` if the node doesn't say
+pub struct NativeToken {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl NativeToken {
+    pub async fn fetch(rpc: &LegacyRpcMethods<PolkadotConfig>) -> Self {
+        let properties = rpc.system_properties().await.unwrap_or_default();
+        Self {
+            symbol: properties.get("tokenSymbol")
+                .and_then(|symbol| symbol.as_str())
+                .unwrap_or("ETRID")
+                .to_string(),
+            decimals: properties.get("tokenDecimals")
+                .and_then(|decimals| decimals.as_u64())
+                .and_then(|decimals| u8::try_from(decimals).ok())
+                .unwrap_or(0),
+        }
+    }
+
+    /// `amount` base units, e.g. `1.5 ETRID`
+    pub fn format(&self, amount: u128) -> String {
+        format!("{} {}", units::format_units(amount, self.decimals), self.symbol)
+    }
+}
+
+/// Free balance of `account` at the latest block; 0 for an account that doesn't exist
+pub async fn fetch_free_balance(client: &OnlineClient<PolkadotConfig>, account: &AccountId32) -> Result<u128> {
+    let address = subxt::dynamic::storage("System", "Account", vec![Value::from_bytes(account.0)]);
+    let value = client.storage()
+        .at_latest()
+        .await
+        .context("Failed to get latest block")?
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch account {}", account))?;
+
+    match value {
+        Some(value) => {
+            let value = value.to_value().context("Failed to decode account info")?;
+            value.at("data")
+                .and_then(|data| data.at("free"))
+                .and_then(|free| free.as_u128())
+                .context(format!("Account {} has no free balance field", account))
+        }
+        None => Ok(0),
+    }
+}
+
+/// `Balances::ExistentialDeposit`, or 0 if the runtime doesn't declare one
+pub fn existential_deposit(client: &OnlineClient<PolkadotConfig>) -> u128 {
+    client.constants()
+        .at(&subxt::dynamic::constant("Balances", "ExistentialDeposit"))
+        .and_then(|thunk| thunk.to_value())
+        .ok()
+        .and_then(|value| value.as_u128())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_funding_shortfall() {
+        let funding = Funding { fees: 1_000, buffer_percent: 20, existential_deposit: 500 };
+        assert_eq!(funding.required(), 1_700);
+        assert_eq!(funding.shortfall(1_000), Some(700));
+        assert_eq!(funding.shortfall(1_700), None);
+        assert_eq!(funding.shortfall(u128::MAX), None);
+
+        let saturated = Funding { fees: u128::MAX, buffer_percent: 20, existential_deposit: 1 };
+        assert_eq!(saturated.required(), u128::MAX);
+    }
+
+    #[test]
+    fn test_format_native() {
+        let token = NativeToken { symbol: "ETRID".to_string(), decimals: 12 };
+        assert_eq!(token.format(1_500_000_000_000), "1.5 ETRID");
+    }
+}
@@ -0,0 +1,148 @@
+//! Routing bridge changes through on-chain governance (`--via-governance`)
+//! on networks where the operator can't sign them directly.
+//!
+//! The call's preimage is noted first so voters can look it up by hash, then
+//! a council motion or a democracy proposal is opened for it. Nothing is
+//! applied until governance passes it.
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use subxt::blocks::ExtrinsicEvents;
+use subxt::config::substrate::BlakeTwo256;
+use subxt::config::Hasher;
+use subxt::dynamic::{At, Value};
+use subxt::tx::DynamicPayload;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+/// Pallet that stores call preimages by hash
+pub const PREIMAGE_PALLET: &str = "Preimage";
+
+/// Collective pallet of the council
+pub const COUNCIL_PALLET: &str = "Council";
+
+/// Pallet for public proposals and referenda
+pub const DEMOCRACY_PALLET: &str = "Democracy";
+
+/// Which governance body a change goes through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Governance {
+    /// A council motion; the signer must be a council member
+    Council,
+    /// A public democracy proposal, backed by the minimum deposit
+    Democracy,
+}
+
+impl Governance {
+    /// Pallet whose `Proposed` event carries the proposal index
+    pub fn pallet(self) -> &'static str {
+        match self {
+            Governance::Council => COUNCIL_PALLET,
+            Governance::Democracy => DEMOCRACY_PALLET,
+        }
+    }
+}
+
+/// Build `preimage.note_preimage(call_data)`
+pub fn note_preimage(call_data: &[u8]) -> DynamicPayload {
+    subxt::dynamic::tx(PREIMAGE_PALLET, "note_preimage", vec![Value::from_bytes(call_data)])
+}
+
+/// Build `council.propose(threshold, call, length_bound)`, a motion that
+/// dispatches `call` once `threshold` members approve it
+pub fn council_propose(threshold: u32, call: DynamicPayload, call_len: usize) -> DynamicPayload {
+    subxt::dynamic::tx(
+        COUNCIL_PALLET,
+        "propose",
+        vec![Value::u128(threshold.into()), call.into_value(), Value::u128(call_len as u128)],
+    )
+}
+
+/// Approvals a council motion needs: `configured` (`--council-threshold`),
+/// else a majority of the `member_count` members
+pub fn council_threshold(configured: Option<u32>, member_count: usize) -> Result<u32> {
+    let threshold = configured.unwrap_or(member_count as u32 / 2 + 1);
+    if threshold == 0 || threshold as usize > member_count {
+        anyhow::bail!("A council threshold of {} can't be met by {} members", threshold, member_count);
+    }
+    Ok(threshold)
+}
+
+/// Build `democracy.propose(Lookup { hash, len }, deposit)` for a noted preimage
+pub fn democracy_propose(call_data: &[u8], deposit: u128) -> DynamicPayload {
+    let proposal = Value::named_variant("Lookup", [
+        ("hash", Value::from_bytes(BlakeTwo256::hash(call_data).0)),
+        ("len", Value::u128(call_data.len() as u128)),
+    ]);
+    subxt::dynamic::tx(DEMOCRACY_PALLET, "propose", vec![proposal, Value::u128(deposit)])
+}
+
+/// Index from the `Proposed` event `pallet` emitted, if any
+pub fn proposal_index(events: &ExtrinsicEvents<PolkadotConfig>, pallet: &str) -> Option<u32> {
+    events.iter()
+        .map_while(|event| event.ok())
+        .find(|event| event.pallet_name() == pallet && event.variant_name() == "Proposed")
+        .and_then(|event| event.field_values().ok())
+        .and_then(|fields| fields.at("proposal_index").and_then(|index| index.as_u128()))
+        .map(|index| index as u32)
+}
+
+/// `Council::Members`, empty if the runtime has no council
+pub async fn fetch_council_members(client: &OnlineClient<PolkadotConfig>) -> Result<Vec<AccountId32>> {
+    let address = subxt::dynamic::storage(COUNCIL_PALLET, "Members", Vec::<Value>::new());
+    let value = client.storage()
+        .at_latest()
+        .await
+        .context("Failed to get latest block")?
+        .fetch(&address)
+        .await
+        .context("Failed to fetch council members")?;
+
+    match value {
+        Some(value) => value.as_type::<Vec<AccountId32>>().context("Failed to decode council members"),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// `Democracy::MinimumDeposit`, the least a public proposal can be backed with
+pub fn minimum_deposit(client: &OnlineClient<PolkadotConfig>) -> Result<u128> {
+    client.constants()
+        .at(&subxt::dynamic::constant(DEMOCRACY_PALLET, "MinimumDeposit"))
+        .and_then(|thunk| thunk.to_value())
+        .context("Runtime has no Democracy::MinimumDeposit")?
+        .as_u128()
+        .context("Democracy::MinimumDeposit is not a number")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proposal_calls() {
+        let call = note_preimage(&[1, 2, 3]);
+        assert_eq!((call.pallet_name(), call.call_name()), (PREIMAGE_PALLET, "note_preimage"));
+
+        let call = council_propose(3, crate::calls::set_fee("tron", 1_000), 12);
+        assert_eq!((call.pallet_name(), call.call_name()), (COUNCIL_PALLET, "propose"));
+
+        let call = democracy_propose(&[1, 2, 3], 100);
+        assert_eq!((call.pallet_name(), call.call_name()), (DEMOCRACY_PALLET, "propose"));
+    }
+
+    #[test]
+    fn test_council_threshold() {
+        assert_eq!(council_threshold(None, 5).unwrap(), 3);
+        assert_eq!(council_threshold(None, 4).unwrap(), 3);
+        assert_eq!(council_threshold(Some(2), 5).unwrap(), 2);
+        assert!(council_threshold(Some(6), 5).is_err());
+        assert!(council_threshold(Some(0), 5).is_err());
+        assert!(council_threshold(None, 0).is_err());
+    }
+
+    #[test]
+    fn test_governance_pallet() {
+        assert_eq!(Governance::Council.pallet(), "Council");
+        assert_eq!(Governance::Democracy.pallet(), "Democracy");
+    }
+}
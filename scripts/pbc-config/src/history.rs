@@ -0,0 +1,111 @@
+//! Sampling a bridge's on-chain state across a block range, to reconstruct
+//! how its configuration changed over time.
+
+use anyhow::Result;
+use subxt::utils::H256;
+
+use crate::plan::Divergence;
+use crate::storage::BridgeState;
+
+/// `chain`'s bridge state as of one block
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub block_number: u64,
+    pub block_hash: H256,
+    pub state: BridgeState,
+    pub confirmations: Option<u32>,
+}
+
+impl Sample {
+    /// Flattened `(field, value)` pairs: the bridge state's, then confirmations
+    pub fn fields(&self) -> Vec<(&'static str, Option<String>)> {
+        let mut fields = self.state.fields();
+        fields.push(("confirmations", self.confirmations.map(|confirmations| confirmations.to_string())));
+        fields
+    }
+
+    /// One flat JSON row per sample, for charting; unset fields are `null`
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut row = serde_json::Map::new();
+        row.insert("block_number".to_string(), self.block_number.into());
+        row.insert("block_hash".to_string(), format!("{:?}", self.block_hash).into());
+        for (field, value) in self.fields() {
+            row.insert(field.to_string(), value.into());
+        }
+        row.into()
+    }
+}
+
+/// Block numbers from `from` to `to` every `step` blocks, always ending on `to`
+pub fn sample_blocks(from: u64, to: u64, step: u64) -> Result<Vec<u64>> {
+    if from > to {
+        anyhow::bail!("--from ({}) must not be greater than --to ({})", from, to);
+    }
+    if step == 0 {
+        anyhow::bail!("--step must be at least 1");
+    }
+    let mut blocks: Vec<u64> = (from..=to).step_by(usize::try_from(step).unwrap_or(usize::MAX)).collect();
+    if blocks.last() != Some(&to) {
+        blocks.push(to);
+    }
+    Ok(blocks)
+}
+
+/// Fields that changed from `before` to `after`, old value on the left
+pub fn changes(before: &Sample, after: &Sample) -> Vec<Divergence> {
+    before.fields()
+        .into_iter()
+        .zip(after.fields())
+        .filter(|((_, left), (_, right))| left != right)
+        .map(|((field, left), (_, right))| Divergence { field: field.to_string(), left, right })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::TokenMapping;
+
+    fn sample(block_number: u64, fee_permill: Option<u32>, confirmations: Option<u32>) -> Sample {
+        Sample {
+            block_number,
+            block_hash: H256::zero(),
+            state: BridgeState {
+                token_mapping: Some(TokenMapping {
+                    token_address: "0xabc".to_string(),
+                    exchange_rate: 1,
+                    decimals: 18,
+                    dest_decimals: 18,
+                }),
+                fee_permill,
+            },
+            confirmations,
+        }
+    }
+
+    #[test]
+    fn test_sample_blocks() {
+        assert_eq!(sample_blocks(100, 130, 10).unwrap(), vec![100, 110, 120, 130]);
+        assert_eq!(sample_blocks(100, 125, 10).unwrap(), vec![100, 110, 120, 125]);
+        assert_eq!(sample_blocks(100, 100, 10).unwrap(), vec![100]);
+        assert!(sample_blocks(130, 100, 10).is_err());
+        assert!(sample_blocks(100, 130, 0).is_err());
+    }
+
+    #[test]
+    fn test_changes() {
+        let before = sample(100, None, Some(12));
+        let after = sample(200, Some(1000), Some(12));
+        assert!(changes(&before, &before).is_empty());
+        assert_eq!(changes(&before, &after), vec![Divergence {
+            field: "fee".to_string(),
+            left: None,
+            right: Some("0.1%".to_string()),
+        }]);
+
+        let row = after.to_json();
+        assert_eq!(row["block_number"], 200);
+        assert_eq!(row["confirmations"], "12");
+        assert_eq!(row["exchange_rate"], "1");
+    }
+}
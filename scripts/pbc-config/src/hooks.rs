@@ -0,0 +1,99 @@
+//! Commands from the config run after an operation succeeds, e.g. to notify
+//! another system that a chain was (re)configured.
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use tracing::info;
+
+use crate::warnings;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Hooks {
+    /// Shell command run after each successful configure or update, with
+    /// `{chain}`, `{block_hash}` and `{status}` (`configured`/`updated`)
+    /// substituted shell-quoted. The same values are in `PBC_CHAIN`,
+    /// `PBC_BLOCK_HASH` and `PBC_STATUS`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_configure: Option<String>,
+    /// Only warn, rather than fail the run, when a hook exits non-zero
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_failure: bool,
+}
+
+/// `template` with its placeholders filled in, each quoted so the shell
+/// sees it as one literal word whatever characters it holds
+pub fn render(template: &str, chain: &str, block_hash: &str, status: &str) -> String {
+    template
+        .replace("{chain}", &shell_quote(chain))
+        .replace("{block_hash}", &shell_quote(block_hash))
+        .replace("{status}", &shell_quote(status))
+}
+
+/// `value` in single quotes, with any single quote inside closed, escaped and reopened
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+impl Hooks {
+    /// Run `post_configure`, if set, for `chain` reaching `status` in `block_hash`,
+    /// logging its output
+    pub fn post_configure(&self, chain: &str, block_hash: &str, status: &str) -> Result<()> {
+        let Some(template) = &self.post_configure else {
+            return Ok(());
+        };
+        let command = render(template, chain, block_hash, status);
+        info!("Running post_configure hook for {}: {}", chain, command);
+
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .env("PBC_CHAIN", chain)
+            .env("PBC_BLOCK_HASH", block_hash)
+            .env("PBC_STATUS", status)
+            .output()
+            .context(format!("Failed to run post_configure hook: {}", command))?;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            info!("hook: {}", line);
+        }
+        for line in String::from_utf8_lossy(&output.stderr).lines() {
+            info!("hook (stderr): {}", line);
+        }
+
+        if !output.status.success() {
+            let problem = format!("post_configure hook for {} failed ({})", chain, output.status);
+            if !self.allow_failure {
+                anyhow::bail!("{}", problem);
+            }
+            warnings::warn(format!("{}; continuing because of allow_failure", problem))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render("notify --chain {chain} --block {block_hash} --status {status}", "solana", "0xabc", "configured"),
+            "notify --chain 'solana' --block '0xabc' --status 'configured'"
+        );
+        assert_eq!(render("echo done", "solana", "0xabc", "updated"), "echo done");
+        assert_eq!(render("echo {chain}", "it's; rm -rf /", "0xabc", "updated"), r"echo 'it'\''s; rm -rf /'");
+    }
+
+    #[test]
+    fn test_post_configure_exit_status() {
+        let hooks = |command: &str, allow_failure| Hooks { post_configure: Some(command.to_string()), allow_failure };
+        assert!(Hooks::default().post_configure("solana", "0xabc", "configured").is_ok());
+        assert!(hooks("test {chain} = solana", false).post_configure("solana", "0xabc", "configured").is_ok());
+        assert!(hooks("exit 3", false).post_configure("solana", "0xabc", "configured").is_err());
+        // A value that is shell syntax reaches the command as one literal word
+        assert!(hooks("test {chain} = 'a; exit 3'", false).post_configure("a; exit 3", "0xabc", "configured").is_ok());
+        assert!(hooks("test \"$PBC_CHAIN $PBC_STATUS\" = 'solana updated'", false).post_configure("solana", "0xabc", "updated").is_ok());
+    }
+}
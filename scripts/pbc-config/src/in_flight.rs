@@ -0,0 +1,82 @@
+//! A process-wide cap on concurrent RPC requests (`--max-in-flight`), applied
+//! beneath subxt so every request to a node counts against it, whichever
+//! command made it.
+
+use std::sync::Arc;
+use subxt::backend::rpc::{RawRpcFuture, RawRpcSubscription, RawValue, RpcClientT};
+use subxt::error::RpcError;
+use tokio::sync::Semaphore;
+
+/// Wraps an RPC client so each request holds a permit from `permits` until
+/// its response arrives. A subscription only holds one while it is opened.
+pub struct Limited<C> {
+    inner: C,
+    permits: Arc<Semaphore>,
+}
+
+impl<C> Limited<C> {
+    pub fn new(inner: C, permits: Arc<Semaphore>) -> Self {
+        Self { inner, permits }
+    }
+}
+
+impl<C: RpcClientT> RpcClientT for Limited<C> {
+    fn request_raw<'a>(&'a self, method: &'a str, params: Option<Box<RawValue>>) -> RawRpcFuture<'a, Box<RawValue>> {
+        Box::pin(async move {
+            let _permit = self.permits.acquire().await.map_err(|e| RpcError::ClientError(Box::new(e)))?;
+            self.inner.request_raw(method, params).await
+        })
+    }
+
+    fn subscribe_raw<'a>(
+        &'a self,
+        sub: &'a str,
+        params: Option<Box<RawValue>>,
+        unsub: &'a str,
+    ) -> RawRpcFuture<'a, RawRpcSubscription> {
+        Box::pin(async move {
+            let _permit = self.permits.acquire().await.map_err(|e| RpcError::ClientError(Box::new(e)))?;
+            self.inner.subscribe_raw(sub, params, unsub).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Answers every request after a pause, tracking how many overlap
+    #[derive(Default)]
+    struct Slow {
+        current: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    impl RpcClientT for Slow {
+        fn request_raw<'a>(&'a self, _: &'a str, _: Option<Box<RawValue>>) -> RawRpcFuture<'a, Box<RawValue>> {
+            Box::pin(async move {
+                let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+                self.peak.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                self.current.fetch_sub(1, Ordering::SeqCst);
+                Ok(RawValue::from_string("null".to_string()).unwrap())
+            })
+        }
+
+        fn subscribe_raw<'a>(&'a self, _: &'a str, _: Option<Box<RawValue>>, _: &'a str) -> RawRpcFuture<'a, RawRpcSubscription> {
+            Box::pin(async { Err(RpcError::SubscriptionDropped) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_capped() {
+        let limited = Limited::new(Slow::default(), Arc::new(Semaphore::new(2)));
+        let requests = (0..6).map(|_| limited.request_raw("system_health", None));
+        for response in futures::future::join_all(requests).await {
+            response.unwrap();
+        }
+        assert_eq!(limited.inner.peak.load(Ordering::SeqCst), 2);
+    }
+}
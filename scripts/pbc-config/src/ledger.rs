@@ -0,0 +1,161 @@
+//! A local SQLite ledger of every submitted extrinsic, written with
+//! `--ledger`, so bridge changes can be audited without a chain explorer.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::path::Path;
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS submissions (
+    id INTEGER PRIMARY KEY,
+    recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    chain TEXT NOT NULL,
+    operation TEXT NOT NULL,
+    params TEXT NOT NULL,
+    call_hash TEXT NOT NULL,
+    block_number INTEGER,
+    nonce INTEGER,
+    fee TEXT,
+    status TEXT NOT NULL,
+    error TEXT
+)";
+
+/// One submitted extrinsic and how it ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Entry {
+    /// The chain, or the label of a multi-chain batch or pre-signed file
+    pub chain: String,
+    /// `Pallet.call`
+    pub operation: String,
+    /// The call's arguments as the node decodes them
+    pub params: String,
+    pub call_hash: String,
+    /// `None` unless the extrinsic was applied
+    pub block_number: Option<u64>,
+    /// `None` for a pre-signed extrinsic whose nonce couldn't be read
+    pub nonce: Option<u64>,
+    /// In the chain's smallest unit; `None` when no fee event was seen
+    pub fee: Option<u128>,
+    /// `finalized`, `in_block`, `failed` or `timed_out`
+    pub status: String,
+    pub error: Option<String>,
+}
+
+/// An entry as read back, with when it was recorded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Row {
+    /// UTC, e.g. `2026-10-15T09:30:00Z`
+    pub recorded_at: String,
+    #[serde(flatten)]
+    pub entry: Entry,
+}
+
+pub struct Ledger {
+    connection: Connection,
+}
+
+impl Ledger {
+    /// Open the ledger at `path`, creating it if needed
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path)
+            .context(format!("Failed to open ledger {}", path.display()))?;
+        connection.execute(SCHEMA, [])
+            .context(format!("Failed to create the ledger table in {}", path.display()))?;
+        Ok(Self { connection })
+    }
+
+    pub fn record(&self, entry: &Entry) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO submissions
+                (chain, operation, params, call_hash, block_number, nonce, fee, status, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                entry.chain,
+                entry.operation,
+                entry.params,
+                entry.call_hash,
+                entry.block_number.map(|number| number as i64),
+                entry.nonce.map(|nonce| nonce as i64),
+                // u128 doesn't fit SQLite's integers
+                entry.fee.map(|fee| fee.to_string()),
+                entry.status,
+                entry.error,
+            ],
+        )
+        .context("Failed to write to the ledger")?;
+        Ok(())
+    }
+
+    /// Entries oldest first, only `chain`'s if given, at most the `limit` newest
+    pub fn list(&self, chain: Option<&str>, limit: Option<u32>) -> Result<Vec<Row>> {
+        let mut statement = self.connection.prepare(
+            "SELECT recorded_at, chain, operation, params, call_hash, block_number, nonce, fee, status, error
+             FROM submissions
+             WHERE ?1 IS NULL OR chain = ?1
+             ORDER BY id DESC
+             LIMIT ?2",
+        )?;
+        let rows = statement.query_map(params![chain, limit.map_or(-1, i64::from)], |row| {
+            Ok(Row {
+                recorded_at: row.get(0)?,
+                entry: Entry {
+                    chain: row.get(1)?,
+                    operation: row.get(2)?,
+                    params: row.get(3)?,
+                    call_hash: row.get(4)?,
+                    block_number: row.get::<_, Option<i64>>(5)?.map(|number| number as u64),
+                    nonce: row.get::<_, Option<i64>>(6)?.map(|nonce| nonce as u64),
+                    fee: row.get::<_, Option<String>>(7)?.and_then(|fee| fee.parse().ok()),
+                    status: row.get(8)?,
+                    error: row.get(9)?,
+                },
+            })
+        })?;
+        let mut entries = rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read the ledger")?;
+        entries.reverse();
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(chain: &str, nonce: u64) -> Entry {
+        Entry {
+            chain: chain.to_string(),
+            operation: "Bridge.set_fee".to_string(),
+            params: "(tron, 3000)".to_string(),
+            call_hash: "0x01".to_string(),
+            block_number: Some(100 + nonce),
+            nonce: Some(nonce),
+            fee: Some(u128::MAX),
+            status: "finalized".to_string(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_ledger_round_trip() {
+        let path = std::env::temp_dir().join(format!("etrid-ledger-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let ledger = Ledger::open(&path).unwrap();
+        ledger.record(&entry("tron", 1)).unwrap();
+        ledger.record(&entry("xrp", 2)).unwrap();
+        ledger.record(&Entry { status: "timed_out".to_string(), block_number: None, fee: None, ..entry("tron", 3) }).unwrap();
+
+        // Reopening keeps what was recorded
+        let ledger = Ledger::open(&path).unwrap();
+        let all = ledger.list(None, None).unwrap();
+        assert_eq!(all.iter().map(|row| row.entry.nonce).collect::<Vec<_>>(), [Some(1), Some(2), Some(3)]);
+        assert_eq!(all[0].entry, entry("tron", 1));
+        assert_eq!(all[2].entry.fee, None);
+        assert!(all[0].recorded_at.ends_with('Z'), "{}", all[0].recorded_at);
+
+        let tron = ledger.list(Some("tron"), Some(1)).unwrap();
+        assert_eq!(tron.len(), 1);
+        assert_eq!(tron[0].entry.status, "timed_out");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
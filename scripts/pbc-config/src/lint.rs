@@ -0,0 +1,234 @@
+//! `lint`: stylistic and consistency warnings about a config that is valid
+//! but likely to cause trouble later. Nothing here is fatal unless `--strict`.
+
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use crate::{chains, redact, Config};
+
+/// One lint warning and the config path it is about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub path: String,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { path: path.into(), message: message.into() }
+    }
+}
+
+/// Every lint warning for `config`, loaded without a profile, sorted by path
+pub fn lint(config: &Config) -> Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    for (name, _) in sorted(&config.profiles) {
+        if is_production_profile(name) {
+            let profile = config.clone().with_profile(name)?;
+            findings.extend(insecure_endpoints(&profile, &format!("profiles.{}.", name)));
+        }
+    }
+    findings.extend(mixed_address_casing(config));
+    findings.extend(missing_recommended_fields(config));
+    findings.extend(round_exchange_rates(config));
+    findings.extend(chains_without_profile(config));
+    findings.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(findings)
+}
+
+fn sorted<V>(map: &std::collections::HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by_key(|(name, _)| *name);
+    entries
+}
+
+fn is_production_profile(name: &str) -> bool {
+    ["prod", "production", "mainnet"].iter().any(|prod| name.eq_ignore_ascii_case(prod))
+}
+
+/// Endpoints a production profile reaches over `ws://` or `http://`
+fn insecure_endpoints(config: &Config, prefix: &str) -> Vec<Finding> {
+    let mut endpoints = vec![
+        ("flarechain.endpoint".to_string(), &config.flarechain.endpoint),
+        ("flarechain.http_endpoint".to_string(), &config.flarechain.http_endpoint),
+    ];
+    for (index, endpoint) in config.flarechain.fallback_endpoints.iter().enumerate() {
+        endpoints.push((format!("flarechain.fallback_endpoints.{}", index), endpoint));
+    }
+    for (index, endpoint) in config.endpoints.iter().enumerate() {
+        endpoints.push((format!("endpoints.{}", index), endpoint));
+    }
+    for (name, chain_config) in sorted(&config.chains) {
+        endpoints.push((format!("chains.{}.pbc_endpoint", name), &chain_config.pbc_endpoint));
+        endpoints.push((format!("chains.{}.http_endpoint", name), &chain_config.http_endpoint));
+        for (index, endpoint) in chain_config.fallback_endpoints.iter().enumerate() {
+            endpoints.push((format!("chains.{}.fallback_endpoints.{}", name, index), endpoint));
+        }
+        if let Some(endpoint) = &chain_config.native_rpc_endpoint {
+            endpoints.push((format!("chains.{}.native_rpc_endpoint", name), endpoint));
+        }
+    }
+
+    endpoints.into_iter()
+        .filter_map(|(path, endpoint)| {
+            let secure = match endpoint.split_once("://").map(|(scheme, _)| scheme) {
+                Some("ws") => "wss",
+                Some("http") => "https",
+                _ => return None,
+            };
+            Some(Finding::new(
+                format!("{}{}", prefix, path),
+                format!("{} is unencrypted; use {}:// in production", redact::endpoint(endpoint), secure),
+            ))
+        })
+        .collect()
+}
+
+/// Addresses written in mixed case where other addresses of the same
+/// family are written in the family's canonical casing, or the other way round
+fn mixed_address_casing(config: &Config) -> Vec<Finding> {
+    let mut by_family: std::collections::BTreeMap<&str, (Vec<String>, usize)> = Default::default();
+    for (name, chain_config) in sorted(&config.chains) {
+        let Some(adapter) = chains::adapter(name) else {
+            continue;
+        };
+        for (field, address) in [("token_address", &chain_config.token_address), ("bridge_address", &chain_config.bridge_address)] {
+            let canonical = adapter.canonical_address(address);
+            let (noncanonical, canonical_count) = by_family.entry(adapter.family()).or_default();
+            if canonical != *address {
+                noncanonical.push(format!("chains.{}.{}", name, field));
+            } else if canonical.chars().any(|c| c.is_ascii_alphabetic()) {
+                *canonical_count += 1;
+            }
+        }
+    }
+
+    by_family.into_iter()
+        .filter(|(_, (noncanonical, canonical_count))| !noncanonical.is_empty() && *canonical_count > 0)
+        .flat_map(|(family, (noncanonical, _))| noncanonical.into_iter().map(move |path| Finding::new(
+            path,
+            format!("{} address casing differs from other {} addresses; `canonicalize` writes them all one way", family, family),
+        )))
+        .collect()
+}
+
+/// Optional fields worth setting: a symbol for humans, and genesis hashes
+/// so a misrouted endpoint fails to connect instead of configuring the wrong chain
+fn missing_recommended_fields(config: &Config) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if config.flarechain.genesis_hash.is_none() {
+        findings.push(Finding::new("flarechain.genesis_hash", "not set; connections can't tell the FlareChain from another network"));
+    }
+    for (name, chain_config) in sorted(&config.chains) {
+        if chain_config.token_symbol.is_none() {
+            findings.push(Finding::new(format!("chains.{}.token_symbol", name), "not set; output shows only the token address"));
+        }
+        if chain_config.genesis_hash.is_none() {
+            findings.push(Finding::new(
+                format!("chains.{}.genesis_hash", name),
+                "not set; connections can't tell this PBC from another network",
+            ));
+        }
+    }
+    findings
+}
+
+/// Exchange rates with a single significant digit (e.g. `2000`), which tend
+/// to be placeholders rather than prices. Powers of ten are left alone since
+/// they are usually pure decimals scaling.
+fn round_exchange_rates(config: &Config) -> Vec<Finding> {
+    sorted(&config.chains).into_iter()
+        .filter_map(|(name, chain_config)| {
+            let rate = chain_config.exchange_rate.as_deref()?;
+            let digits = significant_digits(rate)?;
+            (digits.len() == 1 && digits != "1").then(|| Finding::new(
+                format!("chains.{}.exchange_rate", name),
+                format!("{} has a single significant digit; check it is a real price, not a placeholder", rate),
+            ))
+        })
+        .collect()
+}
+
+/// `value`'s digits without leading or trailing zeros, e.g. `"0.0250"` -> `"25"`
+fn significant_digits(value: &str) -> Option<String> {
+    let mantissa = Decimal::from_str(value.trim()).ok()?.normalize().mantissa().unsigned_abs();
+    Some(mantissa.to_string().trim_end_matches('0').to_string())
+}
+
+/// Chains no profile overrides, when there are profiles at all
+fn chains_without_profile(config: &Config) -> Vec<Finding> {
+    if config.profiles.is_empty() {
+        return Vec::new();
+    }
+    let referenced: BTreeSet<&str> = config.profiles.values()
+        .filter_map(|overrides| overrides.get("chains").and_then(serde_json::Value::as_object))
+        .flat_map(|chains| chains.keys().map(String::as_str))
+        .collect();
+    sorted(&config.chains).into_iter()
+        .filter(|(name, _)| !referenced.contains(name.as_str()))
+        .map(|(name, _)| Finding::new(format!("chains.{}", name), "not referenced by any profile"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_config::{chain, config};
+
+    fn paths(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().map(|finding| finding.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lint() {
+        let mut ethereum = chain(1, "0xdAC17F958D2ee523a2206206994597C13D831ec7", "0x00000000000000000000000000000000000000aa", 6);
+        ethereum["exchange_rate"] = serde_json::json!("2000");
+        ethereum["token_symbol"] = serde_json::json!("USDT");
+        ethereum["genesis_hash"] = serde_json::json!(format!("0x{}", "11".repeat(32)));
+        let mut bnb = chain(2, "0x55d398326f99059ff775485246999027b3197955", "0x00000000000000000000000000000000000000bb", 18);
+        bnb["exchange_rate"] = serde_json::json!("1000000");
+        bnb["token_symbol"] = serde_json::json!("USDT");
+        bnb["genesis_hash"] = serde_json::json!(format!("0x{}", "22".repeat(32)));
+        let mut fixture = config(serde_json::json!({ "ethereum": ethereum, "bnb": bnb }), serde_json::json!({ "ethereum": 12, "bnb": 15 }));
+        fixture["flarechain"]["genesis_hash"] = serde_json::json!(format!("0x{}", "33".repeat(32)));
+        fixture["profiles"] = serde_json::json!({
+            "prod": {
+                "flarechain": { "endpoint": "wss://flare.example.com", "http_endpoint": "https://flare.example.com" },
+                "chains": { "ethereum": { "pbc_endpoint": "wss://eth.example.com", "http_endpoint": "https://eth.example.com" } },
+            },
+        });
+        let config: Config = serde_json::from_value(fixture).unwrap();
+
+        let findings = lint(&config).unwrap();
+        assert_eq!(paths(&findings), [
+            "chains.bnb",
+            "chains.ethereum.exchange_rate",
+            "chains.ethereum.token_address",
+            "profiles.prod.chains.bnb.http_endpoint",
+            "profiles.prod.chains.bnb.pbc_endpoint",
+        ]);
+        assert_eq!(findings[3].message, "http://10.0.0.102:9933 is unencrypted; use https:// in production");
+    }
+
+    #[test]
+    fn test_missing_recommended_fields() {
+        let fixture = config(
+            serde_json::json!({ "tron": chain(1, "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t", "TBridge", 6) }),
+            serde_json::json!({ "tron": 19 }),
+        );
+        let config: Config = serde_json::from_value(fixture).unwrap();
+        assert_eq!(paths(&lint(&config).unwrap()), ["chains.tron.genesis_hash", "chains.tron.token_symbol", "flarechain.genesis_hash"]);
+    }
+
+    #[test]
+    fn test_significant_digits() {
+        assert_eq!(significant_digits("2000").as_deref(), Some("2"));
+        assert_eq!(significant_digits("0.0250").as_deref(), Some("25"));
+        assert_eq!(significant_digits("1000000").as_deref(), Some("1"));
+        assert_eq!(significant_digits("1234.5").as_deref(), Some("12345"));
+        assert_eq!(significant_digits("abc"), None);
+    }
+}
@@ -0,0 +1,36 @@
+//! JSON-RPC calls to an external chain's own node (not its PBC), for checks
+//! only that chain can answer.
+
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+use crate::redact;
+
+/// How long to wait for an external chain's node to answer
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Call `method` on the JSON-RPC node at `endpoint` and return its `result`
+pub async fn call(endpoint: &str, method: &str, params: &serde_json::Value) -> Result<serde_json::Value> {
+    let client = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .context("Failed to build HTTP client")?;
+    let request = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+
+    let response: serde_json::Value = client.post(endpoint)
+        .json(&request)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .context(format!("{} failed at {}", method, redact::endpoint(endpoint)))?
+        .json()
+        .await
+        .context(format!("{} at {} did not return JSON", method, redact::endpoint(endpoint)))?;
+
+    if let Some(error) = response.get("error") {
+        anyhow::bail!("{} at {} returned an error: {}", method, redact::endpoint(endpoint), error);
+    }
+    response.get("result")
+        .cloned()
+        .context(format!("{} at {} returned no result", method, redact::endpoint(endpoint)))
+}
@@ -0,0 +1,150 @@
+//! Catalogue of the bridge operations `configure`, `init` and `update` can perform,
+//! for `pbc-config operations`.
+//!
+//! The catalogue itself is static, but which chains support each operation,
+//! and the argument types the runtime expects, come from each PBC's metadata.
+
+use subxt::Metadata;
+
+use crate::calls::BRIDGE_PALLET;
+
+/// A value an operation takes from the command line or the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub format: &'static str,
+    pub example: &'static str,
+}
+
+/// One bridge call a command can submit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    /// Subcommand that submits it
+    pub command: &'static str,
+    pub name: &'static str,
+    /// Call in the bridge pallet
+    pub call: &'static str,
+    pub parameters: &'static [Parameter],
+}
+
+const AMOUNT: Parameter = Parameter {
+    name: "value",
+    format: "whole number of base units, `_` separators allowed",
+    example: "1_000_000",
+};
+
+const FEE: Parameter = Parameter {
+    name: "value",
+    format: "percent from 0 to 100 with up to 4 decimal places",
+    example: "0.3",
+};
+
+const CONFIRMATIONS: Parameter = Parameter {
+    name: "value",
+    format: "whole number of blocks from 1 to 10000",
+    example: "12",
+};
+
+/// Every operation, in the order `configure` and `init` submit them, then `update`'s
+pub const OPERATIONS: &[Operation] = &[
+    Operation {
+        command: "configure",
+        name: "token-mapping",
+        call: "set_token_mapping",
+        parameters: &[
+            Parameter { name: "token_address", format: "address in the chain's native format", example: "0xdAC17F958D2ee523a2206206994597C13D831ec7" },
+            Parameter { name: "exchange_rate", format: "positive decimal in rate_direction's convention", example: "1000000000000000000" },
+            Parameter { name: "source_decimals", format: "0 to 18", example: "18" },
+            Parameter { name: "dest_decimals", format: "0 to 18, defaults to source_decimals", example: "18" },
+        ],
+    },
+    Operation {
+        command: "configure",
+        name: "fee",
+        call: "set_fee",
+        parameters: &[Parameter { name: "bridge_fee_percent", ..FEE }],
+    },
+    Operation {
+        command: "configure",
+        name: "confirmations",
+        call: "set_confirmations",
+        parameters: &[Parameter {
+            name: "confirmation_blocks",
+            format: "whole number of blocks from 1 to 10000, or { deposit, withdrawal }",
+            example: "12",
+        }],
+    },
+    Operation {
+        command: "configure",
+        name: "withdrawal-confirmations",
+        call: "set_withdrawal_confirmations",
+        parameters: &[Parameter {
+            name: "confirmation_blocks",
+            format: "the withdrawal of { deposit, withdrawal }, else the single number",
+            example: "12",
+        }],
+    },
+    Operation {
+        command: "init",
+        name: "add-relayer",
+        call: "add_relayer",
+        parameters: &[Parameter { name: "relayers[]", format: "SS58 account, one call per relayer not yet registered", example: "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty" }],
+    },
+    Operation {
+        command: "configure",
+        name: "relayer-weight",
+        call: "set_relayer_weight",
+        parameters: &[Parameter { name: "relayers[].weight", format: "whole number, one per configured relayer", example: "1" }],
+    },
+    Operation {
+        command: "init",
+        name: "relayer-threshold",
+        call: "set_relayer_threshold",
+        parameters: &[Parameter {
+            name: "relayer_quorum",
+            format: "whole number of relayers, defaults to a majority of relayers",
+            example: "2",
+        }],
+    },
+    Operation { command: "configure", name: "unpause", call: "unpause", parameters: &[] },
+    Operation { command: "update", name: "max-amount", call: "set_max_amount", parameters: &[AMOUNT] },
+    Operation { command: "update", name: "min-amount", call: "set_min_amount", parameters: &[AMOUNT] },
+    Operation { command: "update", name: "fee", call: "set_fee", parameters: &[FEE] },
+    Operation { command: "update", name: "confirmations", call: "set_confirmations", parameters: &[CONFIRMATIONS] },
+];
+
+/// The runtime's arguments for the bridge pallet's `call` as `name: Type`,
+/// or `None` if the runtime has no such call
+pub fn runtime_arguments(metadata: &Metadata, call: &str) -> Option<Vec<String>> {
+    let variant = metadata.pallet_by_name(BRIDGE_PALLET)?.call_variant_by_name(call)?;
+    Some(variant.fields.iter()
+        .map(|field| format!(
+            "{}: {}",
+            field.name.as_deref().unwrap_or("_"),
+            field.type_name.as_deref().unwrap_or("?")
+        ))
+        .collect())
+}
+
+/// Split `chains` into those whose runtime has `call` and those whose doesn't
+pub fn support<'a>(chains: &'a [(String, Metadata)], call: &str) -> (Vec<&'a str>, Vec<&'a str>) {
+    let (supported, unsupported): (Vec<_>, Vec<_>) = chains.iter()
+        .partition(|(_, metadata)| runtime_arguments(metadata, call).is_some());
+    let names = |chains: Vec<&'a (String, Metadata)>| chains.into_iter().map(|(chain, _)| chain.as_str()).collect();
+    (names(supported), names(unsupported))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calls::UpdateValue;
+
+    #[test]
+    fn test_update_operations_match_parser() {
+        for operation in OPERATIONS.iter().filter(|operation| operation.command == "update") {
+            let value = UpdateValue::parse(operation.name, operation.parameters[0].example)
+                .unwrap_or_else(|e| panic!("{}: {:#}", operation.name, e));
+            assert_eq!(value.call("tron").call_name(), operation.call, "{}", operation.name);
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! Dry-run plans describing what a configure would change on-chain.
+
+use serde::Serialize;
+use similar::TextDiff;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::storage::{BridgeState, TokenMapping};
+
+/// What configuring a chain would do to its token mapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanAction {
+    Create,
+    Update,
+    Unchanged,
+}
+
+/// A single field whose on-chain value differs from the config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub current: Option<String>,
+    pub desired: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChainPlan {
+    pub chain: String,
+    pub action: PlanAction,
+    pub changes: Vec<FieldChange>,
+    /// Estimated partial fee in the chain's smallest unit
+    pub estimated_fee: u128,
+    pub call_data: String,
+    /// Blake2-256 of the encoded call, as governance proposals reference it
+    pub call_hash: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigurePlan {
+    pub chains: Vec<ChainPlan>,
+    pub total_estimated_fee: u128,
+}
+
+impl ConfigurePlan {
+    pub fn new(chains: Vec<ChainPlan>) -> Self {
+        let total_estimated_fee = chains.iter().map(|plan| plan.estimated_fee).sum();
+        Self { chains, total_estimated_fee }
+    }
+}
+
+/// One call `init` still has to make, with the on-chain fields it sets
+#[derive(Debug, Serialize)]
+pub struct InitStep {
+    pub call: String,
+    pub changes: Vec<FieldChange>,
+}
+
+impl std::fmt::Display for InitStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.call)?;
+        for change in &self.changes {
+            write!(
+                f,
+                "\n  {}: {} -> {}",
+                change.field,
+                change.current.as_deref().unwrap_or("unset"),
+                change.desired
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// `field` going from `current` to `desired`, or `None` if it is already there
+pub fn field_change(field: &str, current: Option<String>, desired: String) -> Option<FieldChange> {
+    (current.as_ref() != Some(&desired)).then(|| FieldChange {
+        field: field.to_string(),
+        current,
+        desired,
+    })
+}
+
+/// A field reported differently by two endpoints
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Divergence {
+    pub field: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// Compare the same state as seen from two endpoints
+pub fn diverging_fields(left: &BridgeState, right: &BridgeState) -> Vec<Divergence> {
+    left.fields()
+        .into_iter()
+        .zip(right.fields())
+        .filter(|((_, left), (_, right))| left != right)
+        .map(|((field, left), (_, right))| Divergence {
+            field: field.to_string(),
+            left,
+            right,
+        })
+        .collect()
+}
+
+/// Compare two JSON documents leaf by leaf. Fields are dotted paths
+/// (`chains.tron.exchange_rate`, with array indices as path segments) and
+/// a leaf missing on one side is `None` there.
+pub fn diff_json(left: &serde_json::Value, right: &serde_json::Value) -> Vec<Divergence> {
+    let (mut left_leaves, mut right_leaves) = (BTreeMap::new(), BTreeMap::new());
+    flatten_json(left, String::new(), &mut left_leaves);
+    flatten_json(right, String::new(), &mut right_leaves);
+
+    let fields: BTreeSet<&String> = left_leaves.keys().chain(right_leaves.keys()).collect();
+    fields
+        .into_iter()
+        .filter(|field| left_leaves.get(*field) != right_leaves.get(*field))
+        .map(|field| Divergence {
+            field: field.clone(),
+            left: left_leaves.get(field).cloned(),
+            right: right_leaves.get(field).cloned(),
+        })
+        .collect()
+}
+
+fn flatten_json(value: &serde_json::Value, path: String, leaves: &mut BTreeMap<String, String>) {
+    let child = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    match value {
+        serde_json::Value::Object(object) if !object.is_empty() => {
+            for (key, value) in object {
+                flatten_json(value, child(key), leaves);
+            }
+        }
+        serde_json::Value::Array(array) if !array.is_empty() => {
+            for (index, value) in array.iter().enumerate() {
+                flatten_json(value, child(&index.to_string()), leaves);
+            }
+        }
+        serde_json::Value::String(string) => {
+            leaves.insert(path, string.clone());
+        }
+        other => {
+            leaves.insert(path, other.to_string());
+        }
+    }
+}
+
+/// What taking a config field from `actual` to `expected` does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    Add,
+    Remove,
+    Change,
+}
+
+/// One differing config field, for `--diff-format json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    pub action: ChangeAction,
+}
+
+impl From<Divergence> for ConfigChange {
+    /// `left` is the expected side and `right` the actual one
+    fn from(divergence: Divergence) -> Self {
+        let action = match (&divergence.left, &divergence.right) {
+            (Some(_), None) => ChangeAction::Add,
+            (None, Some(_)) => ChangeAction::Remove,
+            _ => ChangeAction::Change,
+        };
+        Self {
+            path: divergence.field,
+            expected: divergence.left,
+            actual: divergence.right,
+            action,
+        }
+    }
+}
+
+/// A git-style unified diff taking the pretty-printed `actual` JSON to `expected`
+pub fn unified_diff(expected: &serde_json::Value, actual: &serde_json::Value, expected_name: &str, actual_name: &str) -> String {
+    let pretty = |value| serde_json::to_string_pretty(value).unwrap_or_default() + "\n";
+    let (expected, actual) = (pretty(expected), pretty(actual));
+    TextDiff::from_lines(&actual, &expected)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", actual_name), &format!("b/{}", expected_name))
+        .to_string()
+}
+
+/// Compare the desired token mapping against what is currently on-chain
+pub fn diff_token_mapping(
+    desired: &TokenMapping,
+    current: Option<&TokenMapping>,
+) -> (PlanAction, Vec<FieldChange>) {
+    let fields = [
+        ("token_address", desired.token_address.clone(), current.map(|c| c.token_address.clone())),
+        ("exchange_rate", desired.exchange_rate.to_string(), current.map(|c| c.exchange_rate.to_string())),
+        ("decimals", desired.decimals.to_string(), current.map(|c| c.decimals.to_string())),
+        ("dest_decimals", desired.dest_decimals.to_string(), current.map(|c| c.dest_decimals.to_string())),
+    ];
+
+    let changes: Vec<FieldChange> = fields
+        .into_iter()
+        .filter(|(_, desired, current)| current.as_ref() != Some(desired))
+        .map(|(field, desired, current)| FieldChange {
+            field: field.to_string(),
+            current,
+            desired,
+        })
+        .collect();
+
+    let action = match (current, changes.is_empty()) {
+        (None, _) => PlanAction::Create,
+        (Some(_), true) => PlanAction::Unchanged,
+        (Some(_), false) => PlanAction::Update,
+    };
+
+    (action, changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(exchange_rate: u128) -> TokenMapping {
+        TokenMapping {
+            token_address: "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t".to_string(),
+            exchange_rate,
+            decimals: 6,
+            dest_decimals: 6,
+        }
+    }
+
+    #[test]
+    fn test_diff_json() {
+        let applied = serde_json::json!({
+            "chains": { "tron": { "exchange_rate": "1000000", "fallback_endpoints": ["ws://a:9944"] } },
+            "relayers": ["5F"],
+        });
+        let proposed = serde_json::json!({
+            "chains": { "tron": { "exchange_rate": "2000000", "fallback_endpoints": [] }, "xrp": { "decimals": 6 } },
+            "relayers": ["5F"],
+        });
+        assert!(diff_json(&applied, &applied).is_empty());
+        assert_eq!(diff_json(&proposed, &applied), vec![
+            Divergence {
+                field: "chains.tron.exchange_rate".to_string(),
+                left: Some("2000000".to_string()),
+                right: Some("1000000".to_string()),
+            },
+            Divergence {
+                field: "chains.tron.fallback_endpoints".to_string(),
+                left: Some("[]".to_string()),
+                right: None,
+            },
+            Divergence {
+                field: "chains.tron.fallback_endpoints.0".to_string(),
+                left: None,
+                right: Some("ws://a:9944".to_string()),
+            },
+            Divergence {
+                field: "chains.xrp.decimals".to_string(),
+                left: Some("6".to_string()),
+                right: None,
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_config_changes() {
+        let applied = serde_json::json!({ "chains": { "tron": { "exchange_rate": "1000000", "tags": ["evm"] } } });
+        let proposed = serde_json::json!({ "chains": { "tron": { "exchange_rate": "2000000" }, "xrp": { "decimals": 6 } } });
+
+        let changes: Vec<ConfigChange> = diff_json(&proposed, &applied).into_iter().map(ConfigChange::from).collect();
+        let actions: Vec<_> = changes.iter().map(|change| (change.path.as_str(), change.action)).collect();
+        assert_eq!(actions, [
+            ("chains.tron.exchange_rate", ChangeAction::Change),
+            ("chains.tron.tags.0", ChangeAction::Remove),
+            ("chains.xrp.decimals", ChangeAction::Add),
+        ]);
+        assert_eq!(changes[0].expected.as_deref(), Some("2000000"));
+        assert_eq!(changes[0].actual.as_deref(), Some("1000000"));
+
+        let diff = unified_diff(&proposed, &applied, "config.json", "applied.json");
+        assert!(diff.starts_with("--- a/applied.json\n+++ b/config.json\n@@"), "{}", diff);
+        assert!(diff.contains("\n-      \"exchange_rate\": \"1000000\",\n"), "{}", diff);
+        assert!(diff.contains("\n+      \"exchange_rate\": \"2000000\"\n"), "{}", diff);
+        assert!(diff.contains("\n+    \"xrp\": {\n"), "{}", diff);
+        assert!(unified_diff(&applied, &applied, "a", "b").is_empty());
+    }
+
+    #[test]
+    fn test_diverging_fields() {
+        let pbc = BridgeState { token_mapping: Some(mapping(1_000_000)), fee_permill: Some(1_000) };
+        assert!(diverging_fields(&pbc, &pbc.clone()).is_empty());
+
+        let relay = BridgeState { token_mapping: Some(mapping(2_000_000)), fee_permill: None };
+        let divergences = diverging_fields(&pbc, &relay);
+        assert_eq!(divergences, vec![
+            Divergence {
+                field: "exchange_rate".to_string(),
+                left: Some("1000000".to_string()),
+                right: Some("2000000".to_string()),
+            },
+            Divergence {
+                field: "fee".to_string(),
+                left: Some("0.1%".to_string()),
+                right: None,
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_diff_token_mapping() {
+        let (action, changes) = diff_token_mapping(&mapping(1_000_000), None);
+        assert_eq!(action, PlanAction::Create);
+        assert_eq!(changes.len(), 4);
+
+        let (action, changes) = diff_token_mapping(&mapping(1_000_000), Some(&mapping(1_000_000)));
+        assert_eq!(action, PlanAction::Unchanged);
+        assert!(changes.is_empty());
+
+        let (action, changes) = diff_token_mapping(&mapping(2_000_000), Some(&mapping(1_000_000)));
+        assert_eq!(action, PlanAction::Update);
+        assert_eq!(changes, vec![FieldChange {
+            field: "exchange_rate".to_string(),
+            current: Some("1000000".to_string()),
+            desired: "2000000".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn test_init_step() {
+        assert_eq!(field_change("confirmations", Some("12".to_string()), "12".to_string()), None);
+        let change = field_change("confirmations", None, "12".to_string()).unwrap();
+
+        let step = InitStep {
+            call: "set_confirmations".to_string(),
+            changes: vec![change],
+        };
+        assert_eq!(step.to_string(), "set_confirmations\n  confirmations: unset -> 12");
+    }
+}
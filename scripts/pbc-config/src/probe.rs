@@ -0,0 +1,126 @@
+//! Measuring endpoint latency to pick the fastest of several candidates, and
+//! checking endpoints are alive for `verify-all --probe-only`.
+
+use anyhow::{Context, Result};
+use std::time::{Duration, Instant};
+use subxt::backend::legacy::LegacyRpcMethods;
+use subxt::backend::rpc::{rpc_params, RpcClient};
+use subxt::PolkadotConfig;
+use tracing::{info, warn};
+
+use crate::redact;
+
+/// How long a single probe may take before the endpoint counts as unreachable
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time to open a connection to `endpoint` and complete one trivial RPC
+async fn probe(endpoint: &str) -> Result<Duration> {
+    let started = Instant::now();
+    let round_trip = async {
+        let rpc = RpcClient::from_url(endpoint).await.context("Failed to connect")?;
+        let _: serde_json::Value = rpc.request("system_health", rpc_params![]).await
+            .context("system_health failed")?;
+        Ok::<_, anyhow::Error>(())
+    };
+    tokio::time::timeout(PROBE_TIMEOUT, round_trip)
+        .await
+        .map_err(|_| anyhow::anyhow!("no response within {}s", PROBE_TIMEOUT.as_secs()))??;
+    Ok(started.elapsed())
+}
+
+/// Probe every endpoint concurrently and return them fastest first, logging
+/// each measurement. Unreachable endpoints are left out unless none answered.
+pub async fn by_latency(endpoints: &[String]) -> Vec<String> {
+    let results = futures::future::join_all(endpoints.iter().map(|endpoint| async move {
+        (endpoint.clone(), probe(endpoint).await)
+    }))
+    .await;
+
+    for (endpoint, result) in &results {
+        match result {
+            Ok(latency) => info!("Endpoint {} answered in {}ms", redact::endpoint(endpoint), latency.as_millis()),
+            Err(e) => warn!("Endpoint {} is unreachable: {:#}", redact::endpoint(endpoint), e),
+        }
+    }
+    rank(results)
+}
+
+/// The endpoint `--probe-only` reached for a chain
+pub struct Presence {
+    pub endpoint: String,
+    pub latency: Duration,
+    /// Whether the runtime there has the pallet, or why its metadata couldn't be read
+    pub pallet: Result<bool>,
+}
+
+/// Probe `endpoints` in order and check the first that answers has `pallet`.
+/// Fails when none answers, with the last endpoint's error.
+pub async fn pallet_presence(endpoints: &[String], pallet: &str) -> Result<Presence> {
+    let mut last_error = anyhow::anyhow!("no endpoints configured");
+    for endpoint in endpoints {
+        match probe(endpoint).await {
+            Ok(latency) => {
+                let pallet = tokio::time::timeout(PROBE_TIMEOUT, has_pallet(endpoint, pallet))
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("no metadata within {}s", PROBE_TIMEOUT.as_secs())));
+                return Ok(Presence { endpoint: endpoint.clone(), latency, pallet });
+            }
+            Err(e) => {
+                warn!("Endpoint {} is unreachable: {:#}", redact::endpoint(endpoint), e);
+                last_error = e.context(format!("{} is unreachable", redact::endpoint(endpoint)));
+            }
+        }
+    }
+    Err(last_error)
+}
+
+/// Whether the runtime at `endpoint` has `pallet`, from its metadata
+async fn has_pallet(endpoint: &str, pallet: &str) -> Result<bool> {
+    let rpc = RpcClient::from_url(endpoint).await.context("Failed to connect")?;
+    let metadata = LegacyRpcMethods::<PolkadotConfig>::new(rpc).state_get_metadata(None).await
+        .context("state_getMetadata failed")?;
+    Ok(metadata.pallet_by_name(pallet).is_some())
+}
+
+fn rank(results: Vec<(String, Result<Duration>)>) -> Vec<String> {
+    let mut reachable: Vec<(String, Duration)> = results.iter()
+        .filter_map(|(endpoint, result)| result.as_ref().ok().map(|latency| (endpoint.clone(), *latency)))
+        .collect();
+    if reachable.is_empty() {
+        return results.into_iter().map(|(endpoint, _)| endpoint).collect();
+    }
+    reachable.sort_by_key(|(_, latency)| *latency);
+    reachable.into_iter().map(|(endpoint, _)| endpoint).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank() {
+        let ms = Duration::from_millis;
+        let results = vec![
+            ("ws://a".to_string(), Ok(ms(80))),
+            ("ws://b".to_string(), Err(anyhow::anyhow!("refused"))),
+            ("ws://c".to_string(), Ok(ms(15))),
+        ];
+        assert_eq!(rank(results), vec!["ws://c", "ws://a"]);
+
+        // With nothing reachable, keep the configured order so connecting reports the real errors
+        let results = vec![
+            ("ws://a".to_string(), Err(anyhow::anyhow!("refused"))),
+            ("ws://b".to_string(), Err(anyhow::anyhow!("timeout"))),
+        ];
+        assert_eq!(rank(results), vec!["ws://a", "ws://b"]);
+    }
+
+    #[tokio::test]
+    async fn test_pallet_presence_unreachable() {
+        let err = pallet_presence(&[], "Bridge").await.err().unwrap();
+        assert_eq!(err.to_string(), "no endpoints configured");
+
+        let err = pallet_presence(&["ws://127.0.0.1:1".to_string()], "Bridge").await.err().unwrap();
+        assert_eq!(err.to_string(), "ws://127.0.0.1:1 is unreachable");
+    }
+}
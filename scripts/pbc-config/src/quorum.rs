@@ -0,0 +1,71 @@
+//! Deciding whether enough relayers are active for a configured bridge to
+//! actually operate.
+
+use subxt::utils::AccountId32;
+
+/// One configured relayer's on-chain standing for a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayerStatus {
+    pub account: AccountId32,
+    /// Registered in `Bridge::Relayers` for the chain
+    pub registered: bool,
+    /// Block of the relayer's last `Bridge::RelayerHeartbeats` entry
+    pub last_heartbeat: Option<u64>,
+}
+
+impl RelayerStatus {
+    /// Why the relayer doesn't count as active at block `now`, or `None` if it
+    /// does. With `max_age` (the runtime records heartbeats) it must also have
+    /// sent one within that many blocks.
+    pub fn inactive_reason(&self, now: u64, max_age: Option<u64>) -> Option<String> {
+        if !self.registered {
+            return Some("not registered".to_string());
+        }
+        let max_age = max_age?;
+        match self.last_heartbeat {
+            None => Some("no heartbeat yet".to_string()),
+            Some(at) if now.saturating_sub(at) > max_age => {
+                Some(format!("last heartbeat {} blocks ago", now.saturating_sub(at)))
+            }
+            Some(_) => None,
+        }
+    }
+}
+
+/// Relayers that must be active: `configured` (`relayer_quorum`), else a
+/// majority of the `relayer_count` configured relayers
+pub fn threshold(configured: Option<u32>, relayer_count: usize) -> usize {
+    match configured {
+        Some(quorum) => quorum as usize,
+        None => relayer_count / 2 + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(registered: bool, last_heartbeat: Option<u64>) -> RelayerStatus {
+        RelayerStatus { account: AccountId32([1; 32]), registered, last_heartbeat }
+    }
+
+    #[test]
+    fn test_inactive_reason() {
+        assert_eq!(status(false, Some(100)).inactive_reason(100, Some(10)).as_deref(), Some("not registered"));
+        assert_eq!(status(true, None).inactive_reason(100, None), None);
+        assert_eq!(status(true, None).inactive_reason(100, Some(10)).as_deref(), Some("no heartbeat yet"));
+        assert_eq!(status(true, Some(95)).inactive_reason(100, Some(10)), None);
+        assert_eq!(
+            status(true, Some(50)).inactive_reason(100, Some(10)).as_deref(),
+            Some("last heartbeat 50 blocks ago")
+        );
+    }
+
+    #[test]
+    fn test_threshold() {
+        assert_eq!(threshold(None, 3), 2);
+        assert_eq!(threshold(None, 4), 3);
+        assert_eq!(threshold(None, 0), 1);
+        assert_eq!(threshold(Some(1), 3), 1);
+    }
+}
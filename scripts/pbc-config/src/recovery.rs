@@ -0,0 +1,108 @@
+//! Recovery files for extrinsics that were submitted but not confirmed within
+//! `--finality-timeout`, for `recover --from`.
+//!
+//! A timeout leaves it unknown whether the change was applied. The file keeps
+//! the signed extrinsic itself rather than just the call: resubmitting those
+//! exact bytes reuses their nonce, so the change can apply at most once.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::calls::UpdateValue;
+
+/// The change a timed-out extrinsic was meant to make, re-checked before resubmitting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Intent {
+    /// `configure`, `init` or a `configure-all` batch: each chain's bridge
+    /// should match the config
+    Configure { chains: Vec<String> },
+    /// `update`: `parameter` of `chain`'s bridge should be `value`
+    Update { chain: String, parameter: String, value: UpdateValue },
+}
+
+impl fmt::Display for Intent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Intent::Configure { chains } => write!(f, "configuring {}", chains.join(", ")),
+            Intent::Update { chain, parameter, value } => write!(f, "updating {} for {} to {}", parameter, chain, value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recovery {
+    pub intent: Intent,
+    /// `Pallet.call` the extrinsic makes
+    pub call: String,
+    /// Account that signed the extrinsic
+    pub signer: String,
+    pub nonce: u64,
+    /// Genesis and runtime the extrinsic was signed for; it is only valid on
+    /// the same chain, and only safe to resubmit on the same runtime
+    pub genesis_hash: String,
+    pub spec_version: u32,
+    /// The signed extrinsic, `0x`-prefixed hex
+    pub extrinsic: String,
+}
+
+impl Recovery {
+    /// The signed extrinsic's bytes
+    pub fn extrinsic_bytes(&self) -> Result<Vec<u8>> {
+        crate::signed_tx::parse_hex(&self.extrinsic)
+    }
+
+    /// File name in the recovery directory; a signer's nonce identifies the extrinsic
+    pub fn file_name(&self) -> String {
+        format!("recovery-{}-{}.json", self.signer, self.nonce)
+    }
+}
+
+/// Write `recovery` into `dir`, returning the file's path
+pub fn write(dir: &Path, recovery: &Recovery) -> Result<PathBuf> {
+    let path = dir.join(recovery.file_name());
+    let content = serde_json::to_string_pretty(recovery)?;
+    std::fs::write(&path, content + "\n")
+        .context(format!("Failed to write recovery file {}", path.display()))?;
+    Ok(path)
+}
+
+/// Read a recovery file written by [`write`]
+pub fn read(path: &Path) -> Result<Recovery> {
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read recovery file {}", path.display()))?;
+    serde_json::from_str(&content).context(format!("Invalid recovery file {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery_round_trip() {
+        let dir = std::env::temp_dir().join(format!("etrid-recovery-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let recovery = Recovery {
+            intent: Intent::Update { chain: "tron".to_string(), parameter: "fee".to_string(), value: UpdateValue::Fee(3_000) },
+            call: "Bridge.set_fee".to_string(),
+            signer: "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(),
+            nonce: 7,
+            genesis_hash: format!("0x{}", "ab".repeat(32)),
+            spec_version: 100,
+            extrinsic: "0x2a00".to_string(),
+        };
+        let path = write(&dir, &recovery).unwrap();
+        assert_eq!(path.file_name().unwrap(), "recovery-5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY-7.json");
+        assert_eq!(read(&path).unwrap(), recovery);
+        assert_eq!(recovery.extrinsic_bytes().unwrap(), [0x2a, 0x00]);
+        assert_eq!(recovery.intent.to_string(), "updating fee for tron to 0.3%");
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains(r#""kind": "update""#), "{}", content);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
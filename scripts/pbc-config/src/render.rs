@@ -0,0 +1,75 @@
+//! Rendering decoded call arguments and runtime values as text.
+//!
+//! Byte strings (chain names, addresses) show as text when printable and as
+//! hex otherwise, rather than as lists of numbers; nested calls show as
+//! `call(arguments)`, and variants without fields by name alone.
+
+use subxt::ext::scale_value::{Composite, Primitive, Value, ValueDef};
+
+/// Call arguments as the ledger stores them and dry-run results show them
+pub fn params(params: &Composite<()>) -> String {
+    composite(params)
+}
+
+fn composite(fields: &Composite<()>) -> String {
+    match fields {
+        Composite::Named(fields) => {
+            let fields: Vec<String> = fields.iter()
+                .map(|(name, field)| format!("{}: {}", name, value(field)))
+                .collect();
+            format!("{{ {} }}", fields.join(", "))
+        }
+        Composite::Unnamed(values) => {
+            let values: Vec<String> = values.iter().map(value).collect();
+            format!("({})", values.join(", "))
+        }
+    }
+}
+
+/// One value as [`params`] shows it
+pub fn value(value: &Value<()>) -> String {
+    match &value.value {
+        ValueDef::Composite(Composite::Unnamed(values)) => match as_bytes(values) {
+            Some(bytes) => match String::from_utf8(bytes.clone()) {
+                Ok(text) if !text.chars().any(char::is_control) => format!("{:?}", text),
+                _ => format!("0x{}", hex::encode(bytes)),
+            },
+            None => composite(&Composite::Unnamed(values.clone())),
+        },
+        ValueDef::Composite(fields) => composite(fields),
+        ValueDef::Variant(variant) if variant.values.is_empty() => variant.name.clone(),
+        ValueDef::Variant(variant) => format!("{}{}", variant.name, composite(&variant.values)),
+        _ => value.to_string(),
+    }
+}
+
+/// `values` as bytes, if it is a non-empty list of numbers that fit one
+fn as_bytes(values: &[Value<()>]) -> Option<Vec<u8>> {
+    if values.is_empty() {
+        return None;
+    }
+    values.iter()
+        .map(|value| match value.value {
+            ValueDef::Primitive(Primitive::U128(number)) => u8::try_from(number).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params() {
+        let fee = crate::calls::set_fee("tron", 3_000);
+        assert_eq!(params(fee.call_data()), r#"("tron", 3000)"#);
+
+        let relayer = subxt::utils::AccountId32([0xab; 32]);
+        let weight = crate::calls::set_relayer_weight("xrp", &relayer, 2);
+        assert_eq!(params(weight.call_data()), format!(r#"("xrp", 0x{}, 2)"#, "ab".repeat(32)));
+
+        let batch = crate::calls::batch_all(vec![fee]);
+        assert_eq!(params(batch.call_data()), r#"((Bridge(set_fee("tron", 3000))))"#);
+    }
+}
@@ -0,0 +1,392 @@
+//! Machine-readable report of a `ConfigureAll` run, written with `--report-file`.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::plan::Divergence;
+use crate::style;
+use crate::timing::{PhaseTiming, Timings};
+use crate::{ChainOutcome, TableStyle};
+
+/// Bumped whenever the report layout changes incompatibly
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainStatus {
+    Configured,
+    Failed,
+    NotExecuted,
+    TimedOut,
+}
+
+impl ChainStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChainStatus::Configured => "configured",
+            ChainStatus::Failed => "failed",
+            ChainStatus::NotExecuted => "not executed",
+            ChainStatus::TimedOut => "timed out",
+        }
+    }
+}
+
+/// The mortality an extrinsic was signed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Era {
+    Immortal,
+    /// Valid for `period` blocks from `birth_block`
+    Mortal { birth_block: u64, period: u64 },
+}
+
+impl fmt::Display for Era {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Era::Immortal => write!(f, "immortal"),
+            Era::Mortal { birth_block, period } => write!(f, "mortal for {} blocks from #{}", period, birth_block),
+        }
+    }
+}
+
+/// The replay protection an extrinsic was signed with: its signer's nonce and era
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Submission {
+    pub nonce: u64,
+    pub era: Era,
+}
+
+/// Where a chain's configure call finalized and what it cost
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub block_number: u64,
+    /// `None` when the runtime didn't report a `TransactionFeePaid` event
+    pub fee: Option<u128>,
+    /// Signer nonce the extrinsic used; unset in reports from older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub era: Option<Era>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChainReport {
+    pub chain: String,
+    pub status: ChainStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<Receipt>,
+    #[serde(default)]
+    pub timings: Vec<PhaseTiming>,
+    /// Pool endpoint the chain was configured through, with `--rotate-endpoints`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunReport {
+    pub schema_version: u32,
+    pub chains: Vec<ChainReport>,
+    /// SHA-256 of the canonicalized effective config this run applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_hash: Option<String>,
+    /// Timings not tied to a single chain, such as connecting to the endpoint
+    #[serde(default)]
+    pub other_timings: Vec<PhaseTiming>,
+    /// Whether the run went to a `--fork-endpoint` rather than the real network
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rehearsal: bool,
+}
+
+impl RunReport {
+    pub fn new(
+        outcomes: &[(String, ChainOutcome)],
+        timings: &Timings,
+        receipts: &HashMap<String, Receipt>,
+        endpoint: &str,
+    ) -> Self {
+        let chains = outcomes
+            .iter()
+            .map(|(chain, outcome)| {
+                let (status, error) = status_of(outcome);
+                ChainReport {
+                    chain: chain.clone(),
+                    status,
+                    error,
+                    receipt: receipts.get(chain).copied(),
+                    timings: timings.for_target(chain),
+                    endpoint: None,
+                }
+            })
+            .collect();
+
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            chains,
+            config_hash: None,
+            other_timings: timings.for_target(endpoint),
+            rehearsal: false,
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .context(format!("Failed to write report file {}", path.display()))
+    }
+
+    /// Read a report written by an earlier run, rejecting other schema versions
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read report file {}", path.display()))?;
+        Self::parse(&content).context(format!("Invalid report file {}", path.display()))
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let report: Self = serde_json::from_str(content)?;
+        if report.schema_version != REPORT_SCHEMA_VERSION {
+            anyhow::bail!(
+                "report schema version {} is not supported, expected {}",
+                report.schema_version,
+                REPORT_SCHEMA_VERSION
+            );
+        }
+        Ok(report)
+    }
+
+    /// Chains that still need configuring: failures (including timeouts) and
+    /// anything a stopped run never reached
+    pub fn chains_to_retry(&self) -> Vec<String> {
+        self.chains
+            .iter()
+            .filter(|chain| chain.status != ChainStatus::Configured)
+            .map(|chain| chain.chain.clone())
+            .collect()
+    }
+}
+
+fn status_of(outcome: &ChainOutcome) -> (ChainStatus, Option<String>) {
+    match outcome {
+        ChainOutcome::Configured => (ChainStatus::Configured, None),
+        ChainOutcome::Failed(reason) => (ChainStatus::Failed, Some(reason.clone())),
+        ChainOutcome::NotExecuted => (ChainStatus::NotExecuted, None),
+        ChainOutcome::TimedOut => (ChainStatus::TimedOut, None),
+    }
+}
+
+/// The `--summary-only` table: one row per chain with its status, and the
+/// block and fee of its configure call when it finalized
+pub fn summary_table(
+    outcomes: &[(String, ChainOutcome)],
+    receipts: &HashMap<String, Receipt>,
+    table_style: TableStyle,
+) -> String {
+    let rows = outcomes
+        .iter()
+        .map(|(chain, outcome)| {
+            let receipt = receipts.get(chain);
+            vec![
+                chain.clone(),
+                status_of(outcome).0.as_str().to_string(),
+                receipt.map_or("-".to_string(), |receipt| format!("#{}", receipt.block_number)),
+                receipt.and_then(|receipt| receipt.fee).map_or("-".to_string(), |fee| fee.to_string()),
+            ]
+        })
+        .collect();
+    style::table(table_style, &["CHAIN", "STATUS", "BLOCK", "FEE"], rows)
+}
+
+/// A field whose live value differs from config.json
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Mismatch {
+    pub field: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl From<Divergence> for Mismatch {
+    fn from(divergence: Divergence) -> Self {
+        Self {
+            field: divergence.field,
+            expected: divergence.left,
+            actual: divergence.right,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChainAssertion {
+    pub chain: String,
+    pub mismatches: Vec<Mismatch>,
+    /// Set when the chain's state could not be read at all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ChainAssertion {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty() && self.error.is_none()
+    }
+}
+
+/// Result of `pbc-config assert`, always printed as JSON
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssertReport {
+    pub schema_version: u32,
+    pub passed: bool,
+    pub chains: Vec<ChainAssertion>,
+}
+
+impl AssertReport {
+    pub fn new(chains: Vec<ChainAssertion>) -> Self {
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            passed: chains.iter().all(ChainAssertion::passed),
+            chains,
+        }
+    }
+}
+
+/// One chain's part of a `query-all` report
+#[derive(Debug, Serialize)]
+pub struct ChainQuery {
+    pub chain: String,
+    /// The chain's `query` report, unset when it could not be read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of `pbc-config query-all`
+#[derive(Debug, Serialize)]
+pub struct QueryReport {
+    pub schema_version: u32,
+    pub query_type: String,
+    pub chains: Vec<ChainQuery>,
+}
+
+impl QueryReport {
+    pub fn new(query_type: &str, chains: Vec<ChainQuery>) -> Self {
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            query_type: query_type.to_string(),
+            chains,
+        }
+    }
+
+    /// Chains whose state could not be read
+    pub fn failed(&self) -> usize {
+        self.chains.iter().filter(|chain| chain.error.is_some()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chains_to_retry() {
+        let report = RunReport::parse(r#"{
+            "schema_version": 1,
+            "chains": [
+                { "chain": "solana", "status": "configured" },
+                { "chain": "tron", "status": "failed", "error": "Extrinsic was not finalized within 300s" },
+                { "chain": "xrp", "status": "not_executed" },
+                { "chain": "bitcoin", "status": "timed_out" }
+            ]
+        }"#).unwrap();
+        assert_eq!(report.chains_to_retry(), vec!["tron", "xrp", "bitcoin"]);
+
+        let err = RunReport::parse(r#"{ "schema_version": 2, "chains": [] }"#).unwrap_err();
+        assert!(err.to_string().contains("schema version 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let outcomes = vec![
+            ("solana".to_string(), ChainOutcome::Configured),
+            ("tron".to_string(), ChainOutcome::Failed("Extrinsic dropped".to_string())),
+            ("xrp".to_string(), ChainOutcome::NotExecuted),
+            ("bitcoin".to_string(), ChainOutcome::TimedOut),
+        ];
+        let receipts = HashMap::from([
+            ("solana".to_string(), Receipt { block_number: 1200, fee: Some(154_000_000), nonce: Some(7), era: Some(Era::Immortal) }),
+        ]);
+
+        assert_eq!(summary_table(&outcomes, &receipts, TableStyle::Plain), [
+            "CHAIN    STATUS        BLOCK  FEE",
+            "solana   configured    #1200  154000000",
+            "tron     failed        -      -",
+            "xrp      not executed  -      -",
+            "bitcoin  timed out     -      -",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn test_receipt_replay_protection() {
+        let receipt = Receipt { block_number: 1200, fee: None, nonce: Some(7), era: Some(Era::Immortal) };
+        assert_eq!(
+            serde_json::to_value(receipt).unwrap(),
+            serde_json::json!({ "block_number": 1200, "fee": null, "nonce": 7, "era": "immortal" })
+        );
+
+        let mortal = Era::Mortal { birth_block: 1190, period: 64 };
+        assert_eq!(mortal.to_string(), "mortal for 64 blocks from #1190");
+        assert_eq!(
+            serde_json::to_value(mortal).unwrap(),
+            serde_json::json!({ "mortal": { "birth_block": 1190, "period": 64 } })
+        );
+
+        // Receipts written before nonces were recorded still read
+        let old: Receipt = serde_json::from_str(r#"{ "block_number": 1200, "fee": 5 }"#).unwrap();
+        assert_eq!((old.nonce, old.era), (None, None));
+    }
+
+    #[test]
+    fn test_assert_report_passed() {
+        let clean = ChainAssertion { chain: "solana".to_string(), mismatches: vec![], error: None };
+        assert!(AssertReport::new(vec![clean]).passed);
+
+        let drifted = ChainAssertion {
+            chain: "tron".to_string(),
+            mismatches: vec![Mismatch::from(Divergence {
+                field: "fee".to_string(),
+                left: Some("0.1%".to_string()),
+                right: Some("0.3%".to_string()),
+            })],
+            error: None,
+        };
+        let unreadable = ChainAssertion {
+            chain: "xrp".to_string(),
+            mismatches: vec![],
+            error: Some("Failed to decode token mapping".to_string()),
+        };
+        assert!(!unreadable.passed());
+        let report = AssertReport::new(vec![drifted]);
+        assert!(!report.passed);
+        assert_eq!(report.chains[0].mismatches[0].expected.as_deref(), Some("0.1%"));
+    }
+
+    #[test]
+    fn test_query_report() {
+        let report = QueryReport::new("parameters", vec![
+            ChainQuery {
+                chain: "solana".to_string(),
+                state: Some(serde_json::json!({ "parameters": { "fee": "0.1%" } })),
+                error: None,
+            },
+            ChainQuery { chain: "xrp".to_string(), state: None, error: Some("Failed to decode".to_string()) },
+        ]);
+        assert_eq!(report.failed(), 1);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["chains"][0]["state"]["parameters"]["fee"], "0.1%");
+        assert!(json["chains"][0].get("error").is_none());
+        assert!(json["chains"][1].get("state").is_none());
+    }
+}
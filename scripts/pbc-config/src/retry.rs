@@ -0,0 +1,210 @@
+//! Backoff-and-retry for RPC providers that throttle us with HTTP 429.
+//!
+//! Only rate-limit errors are retried here; anything else is returned
+//! immediately so real failures still surface on the first attempt.
+//!
+//! Every retry point (this backoff and endpoint failover) also draws from
+//! the [`RetryBudget`] of the operation it runs in, if one is set, so
+//! stacked retry layers can't multiply into an unbounded run.
+
+use anyhow::Result;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+tokio::task_local! {
+    static BUDGET: RetryBudget;
+}
+
+/// Retries left for one operation, shared by every retry point inside it
+#[derive(Debug)]
+pub struct RetryBudget {
+    limit: u32,
+    used: AtomicU32,
+}
+
+impl RetryBudget {
+    pub fn new(limit: u32) -> Self {
+        Self { limit, used: AtomicU32::new(0) }
+    }
+
+    /// Take one retry; `false` once the budget is spent
+    fn try_spend(&self) -> bool {
+        self.used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| (used < self.limit).then_some(used + 1))
+            .is_ok()
+    }
+}
+
+/// Run `operation` with its retries drawn from a fresh budget of `limit`
+/// (unlimited when `None`). An inner budget replaces an outer one.
+pub async fn with_budget<F: Future>(limit: Option<u32>, operation: F) -> F::Output {
+    match limit {
+        Some(limit) => BUDGET.scope(RetryBudget::new(limit), operation).await,
+        None => operation.await,
+    }
+}
+
+/// Spend one retry of the current operation's budget before retrying `what`.
+/// Always allowed outside [`with_budget`].
+pub fn spend_retry(what: &str) -> bool {
+    BUDGET
+        .try_with(|budget| {
+            let allowed = budget.try_spend();
+            if !allowed {
+                warn!("{}: not retrying, all {} retries of --max-total-retries are used", what, budget.limit);
+            }
+            allowed
+        })
+        .unwrap_or(true)
+}
+
+/// Longest single wait between attempts
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitPolicy {
+    /// Retries after the first attempt; 0 disables retrying
+    pub retries: u32,
+    pub base_delay: Duration,
+}
+
+impl RateLimitPolicy {
+    pub fn new(retries: u32) -> Self {
+        Self { retries, base_delay: Duration::from_millis(500) }
+    }
+
+    /// Exponential backoff for `attempt` (0-based), capped, plus up to 50% jitter
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.base_delay.saturating_mul(2u32.saturating_pow(attempt)).min(MAX_DELAY);
+        let jitter_ms = backoff.as_millis() as u64 / 2;
+        let jitter = if jitter_ms == 0 { 0 } else { pseudo_random() % jitter_ms };
+        backoff + Duration::from_millis(jitter)
+    }
+}
+
+/// Whether `error` is the provider telling us to slow down
+pub fn is_rate_limited(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        let message = cause.to_string().to_ascii_lowercase();
+        message.contains("429")
+            || message.contains("too many requests")
+            || message.contains("rate limit")
+    })
+}
+
+/// Run `operation`, retrying with backoff while it fails with a rate-limit error
+pub async fn with_rate_limit_retry<T, F, Fut>(policy: RateLimitPolicy, what: &str, mut operation: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation().await {
+            Err(e) if attempt < policy.retries && is_rate_limited(&e) && spend_retry(what) => {
+                let delay = policy.delay(attempt);
+                attempt += 1;
+                warn!(
+                    "{} was rate limited, retrying in {}ms ({}/{})",
+                    what, delay.as_millis(), attempt, policy.retries
+                );
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Cheap jitter source; spreading retries out doesn't need a real RNG
+fn pseudo_random() -> u64 {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos() as u64;
+    let mut x = nanos ^ 0x9E37_79B9_7F4A_7C15;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_rate_limited() {
+        let throttled = anyhow::anyhow!("Networking or low-level protocol error: HTTP 429 Too Many Requests")
+            .context("Failed to fetch token mapping for tron");
+        assert!(is_rate_limited(&throttled));
+        assert!(!is_rate_limited(&anyhow::anyhow!("Connection refused")));
+    }
+
+    #[test]
+    fn test_delay_is_capped() {
+        let policy = RateLimitPolicy::new(10);
+        assert!(policy.delay(0) >= Duration::from_millis(500));
+        assert!(policy.delay(0) < Duration::from_millis(750));
+        assert!(policy.delay(20) <= MAX_DELAY + MAX_DELAY / 2);
+    }
+
+    #[tokio::test]
+    async fn test_retries_only_rate_limits() {
+        let policy = RateLimitPolicy { retries: 3, base_delay: Duration::from_millis(1) };
+
+        let calls = AtomicU32::new(0);
+        let result = with_rate_limit_retry(policy, "query", || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(anyhow::anyhow!("429 Too Many Requests")),
+                _ => Ok("ok"),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = AtomicU32::new(0);
+        let result: Result<()> = with_rate_limit_retry(policy, "query", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow::anyhow!("Bad origin"))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = AtomicU32::new(0);
+        let result: Result<()> = with_rate_limit_retry(policy, "query", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow::anyhow!("rate limit exceeded"))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_budget_is_shared_across_retry_points() {
+        let policy = RateLimitPolicy { retries: 3, base_delay: Duration::from_millis(1) };
+        let calls = AtomicU32::new(0);
+        let throttled = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(anyhow::anyhow!("429 Too Many Requests"))
+        };
+
+        let failed_over = with_budget(Some(4), async {
+            // Two retries here leave two for everything after
+            assert!(with_rate_limit_retry(RateLimitPolicy { retries: 2, ..policy }, "query", throttled).await.is_err());
+            assert!(with_rate_limit_retry(policy, "submission", throttled).await.is_err());
+            spend_retry("failover")
+        })
+        .await;
+        // 3 attempts, then 3 more (two retries left), then no budget for failover
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+        assert!(!failed_over);
+
+        // Outside a budget only the per-call limit applies
+        calls.store(0, Ordering::SeqCst);
+        assert!(with_rate_limit_retry(policy, "query", throttled).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert!(spend_retry("failover"));
+    }
+}
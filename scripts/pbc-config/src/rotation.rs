@@ -0,0 +1,46 @@
+//! `configure-all --rotate-endpoints`: spreading chains round-robin across the
+//! config's `endpoints` pool of FlareChain replicas, so no single node carries
+//! the whole run.
+//!
+//! The pool connection a chain uses is task-local, like the retry budget, so
+//! every query and submission made while configuring it goes to the same node.
+
+use std::future::Future;
+
+tokio::task_local! {
+    static SLOT: usize;
+}
+
+/// The pool slot chain number `index` of a run uses, or `None` without a pool
+pub fn slot_for(index: usize, pool_size: usize) -> Option<usize> {
+    (pool_size > 0).then(|| index % pool_size)
+}
+
+/// Run `operation` against pool connection `slot`, or the main connection when `None`
+pub async fn with_slot<F: Future>(slot: Option<usize>, operation: F) -> F::Output {
+    match slot {
+        Some(slot) => SLOT.scope(slot, operation).await,
+        None => operation.await,
+    }
+}
+
+/// The pool slot of the operation running now, if it was given one
+pub fn current() -> Option<usize> {
+    SLOT.try_with(|slot| *slot).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_slot() {
+        let slots: Vec<_> = (0..5).map(|index| slot_for(index, 2)).collect();
+        assert_eq!(slots, [Some(0), Some(1), Some(0), Some(1), Some(0)]);
+        assert_eq!(slot_for(3, 0), None);
+
+        assert_eq!(current(), None);
+        assert_eq!(with_slot(Some(1), async { current() }).await, Some(1));
+        assert_eq!(with_slot(None, async { current() }).await, None);
+    }
+}
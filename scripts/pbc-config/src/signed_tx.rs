@@ -0,0 +1,205 @@
+//! Pre-signed extrinsic files for the `sign` / `submit --from` split, where
+//! one person signs and another submits.
+
+use anyhow::{Context, Result};
+use parity_scale_codec::{Compact, Decode};
+use std::path::Path;
+use subxt::ext::scale_value::{self, Composite, Value, ValueDef};
+use subxt::Metadata;
+
+/// Write `extrinsic` to `path` as a `0x`-prefixed hex line
+pub fn write(path: &Path, extrinsic: &[u8]) -> Result<()> {
+    std::fs::write(path, format!("0x{}\n", hex::encode(extrinsic)))
+        .context(format!("Failed to write signed extrinsic to {}", path.display()))
+}
+
+/// Read an extrinsic written by [`write`]
+pub fn read(path: &Path) -> Result<Vec<u8>> {
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read signed extrinsic from {}", path.display()))?;
+    parse_hex(&content).context(format!("Invalid signed extrinsic in {}", path.display()))
+}
+
+/// `0x`-prefixed (or bare) hex as bytes
+pub fn parse_hex(content: &str) -> Result<Vec<u8>> {
+    let content = content.trim();
+    let hex_str = content.strip_prefix("0x").unwrap_or(content);
+    hex::decode(hex_str).context("expected a hex-encoded extrinsic")
+}
+
+/// What a signed extrinsic calls, and with which nonce
+#[derive(Debug, Clone)]
+pub struct SignedCall {
+    /// `Pallet.call`, e.g. `Bridge.set_fee`
+    pub name: String,
+    pub args: Composite<()>,
+    /// SCALE-encoded call, as its call hash is taken over
+    pub call_data: Vec<u8>,
+    /// `None` when the runtime has no `CheckNonce` extension
+    pub nonce: Option<u64>,
+}
+
+/// Decode a signed extrinsic against `metadata`.
+///
+/// subxt only submits raw bytes, so this is what catches a payload built for
+/// a different runtime before it reaches the pool.
+pub fn decode_call(extrinsic: &[u8], metadata: &Metadata) -> Result<SignedCall> {
+    let cursor = &mut &extrinsic[..];
+    let length = Compact::<u32>::decode(cursor).context("Missing extrinsic length prefix")?.0 as usize;
+    if cursor.len() != length {
+        anyhow::bail!("Extrinsic length prefix says {} bytes but {} follow", length, cursor.len());
+    }
+
+    let version = u8::decode(cursor).context("Missing extrinsic version")?;
+    if version & 0x7f != metadata.extrinsic().version() {
+        anyhow::bail!(
+            "Extrinsic version {} does not match the runtime's {}",
+            version & 0x7f,
+            metadata.extrinsic().version()
+        );
+    }
+    if version & 0x80 == 0 {
+        anyhow::bail!("Extrinsic is not signed");
+    }
+
+    let types = metadata.types();
+    let extrinsic_metadata = metadata.extrinsic();
+    let mut decode = |what: &str, type_id: u32| {
+        scale_value::scale::decode_as_type(cursor, &type_id, types)
+            .map_err(|e| anyhow::anyhow!("Failed to decode {}: {}", what, e))
+    };
+    decode("signer address", extrinsic_metadata.address_ty())?;
+    decode("signature", extrinsic_metadata.signature_ty())?;
+    let mut nonce = None;
+    for extension in extrinsic_metadata.signed_extensions() {
+        let extra = decode(extension.identifier(), extension.extra_ty())?;
+        if extension.identifier() == "CheckNonce" {
+            nonce = extra.as_u128().and_then(|nonce| u64::try_from(nonce).ok());
+        }
+    }
+    // The call is what's left
+    let call_data = cursor.to_vec();
+    let call = scale_value::scale::decode_as_type(cursor, &extrinsic_metadata.call_ty(), types)
+        .map_err(|e| anyhow::anyhow!("Failed to decode call: {}", e))?;
+
+    if !cursor.is_empty() {
+        anyhow::bail!("{} trailing bytes after the call", cursor.len());
+    }
+
+    let (name, args) = runtime_call(&call.map_context(|_| ()))?;
+    Ok(SignedCall { name, args, call_data, nonce })
+}
+
+impl SignedCall {
+    /// The calls this one dispatches as `(Pallet.call, args)`, looking through
+    /// `Sudo.sudo` and `Utility` batches
+    pub fn dispatched(&self) -> Result<Vec<(String, Composite<()>)>> {
+        let mut calls = Vec::new();
+        flatten(&self.name, &self.args, &mut calls)?;
+        Ok(calls)
+    }
+}
+
+fn flatten(name: &str, args: &Composite<()>, calls: &mut Vec<(String, Composite<()>)>) -> Result<()> {
+    match name {
+        "Sudo.sudo" => {
+            let inner = args.values().next().context("Sudo.sudo has no call")?;
+            let (name, args) = runtime_call(inner)?;
+            flatten(&name, &args, calls)
+        }
+        "Utility.batch" | "Utility.batch_all" | "Utility.force_batch" => {
+            let inner = args.values().next().context(format!("{} has no calls", name))?;
+            let ValueDef::Composite(inner) = &inner.value else {
+                anyhow::bail!("{} calls are not a sequence", name);
+            };
+            for call in inner.values() {
+                let (name, args) = runtime_call(call)?;
+                flatten(&name, &args, calls)?;
+            }
+            Ok(())
+        }
+        _ => {
+            calls.push((name.to_string(), args.clone()));
+            Ok(())
+        }
+    }
+}
+
+/// Split a `RuntimeCall` value into `Pallet.call` and its arguments
+fn runtime_call(call: &Value<()>) -> Result<(String, Composite<()>)> {
+    // RuntimeCall is an enum of pallets, each an enum of that pallet's calls
+    let ValueDef::Variant(pallet) = &call.value else {
+        anyhow::bail!("Call is not a pallet variant");
+    };
+    let call_variant = pallet.values.values().next()
+        .and_then(|inner| match &inner.value {
+            ValueDef::Variant(call) => Some(call),
+            _ => None,
+        })
+        .context("Call has no call variant")?;
+    Ok((format!("{}.{}", pallet.name, call_variant.name), call_variant.values.clone()))
+}
+
+/// A decoded argument as bytes, e.g. a chain name or an `AccountId32`
+pub fn arg_bytes(value: &Value<()>) -> Option<Vec<u8>> {
+    let ValueDef::Composite(composite) = &value.value else {
+        return None;
+    };
+    // Newtypes such as AccountId32 decode as a composite around their bytes
+    let mut fields = composite.values();
+    if let (Some(inner @ Value { value: ValueDef::Composite(_), .. }), None) = (fields.next(), fields.next()) {
+        return arg_bytes(inner);
+    }
+    composite.values()
+        .map(|byte| byte.as_u128().and_then(|byte| u8::try_from(byte).ok()))
+        .collect()
+}
+
+/// A decoded numeric argument, looking through newtypes such as `Permill`
+pub fn arg_u128(value: &Value<()>) -> Option<u128> {
+    match &value.value {
+        ValueDef::Composite(composite) if composite.len() == 1 => composite.values().next().and_then(arg_u128),
+        _ => value.as_u128(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("etrid-signed-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("configure-tron.hex");
+
+        write(&path, &[0x84, 0x00, 0xff]).unwrap();
+        assert_eq!(read(&path).unwrap(), vec![0x84, 0x00, 0xff]);
+
+        std::fs::write(&path, "0xnot-hex").unwrap();
+        assert!(read(&path).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_dispatched_looks_through_sudo_and_batches() {
+        let relayer = subxt::utils::AccountId32([7; 32]);
+        let tx = crate::calls::sudo(crate::calls::batch(vec![
+            crate::calls::batch_all(vec![crate::calls::set_fee("tron", 3_000)]),
+            crate::calls::add_relayer("tron", &relayer),
+        ]));
+        let (name, args) = runtime_call(&tx.into_value()).unwrap();
+        let call = SignedCall { name, args, call_data: Vec::new(), nonce: None };
+
+        let dispatched = call.dispatched().unwrap();
+        let names: Vec<_> = dispatched.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Bridge.set_fee", "Bridge.add_relayer"]);
+
+        let fee_args: Vec<_> = dispatched[0].1.values().collect();
+        assert_eq!(arg_bytes(fee_args[0]).unwrap(), b"tron");
+        assert_eq!(arg_u128(fee_args[1]), Some(3_000));
+        let relayer_args: Vec<_> = dispatched[1].1.values().collect();
+        assert_eq!(arg_bytes(relayer_args[1]).unwrap(), [7; 32]);
+    }
+}
@@ -0,0 +1,26 @@
+//! Signing behind a trait object. Where a key comes from (dev account,
+//! mnemonic, file, and later keystores or hardware) is decided once when the
+//! signer is built; everything that submits only sees a `&DynSigner`.
+
+use subxt::tx::Signer;
+use subxt::{Config, PolkadotConfig};
+
+/// Any signer for the chains this tool talks to
+pub type DynSigner = dyn Signer<PolkadotConfig> + Send + Sync;
+
+/// Hands a `&DynSigner` to subxt methods that take a sized `Signer`
+pub struct Forward<'a>(pub &'a DynSigner);
+
+impl Signer<PolkadotConfig> for Forward<'_> {
+    fn account_id(&self) -> <PolkadotConfig as Config>::AccountId {
+        self.0.account_id()
+    }
+
+    fn address(&self) -> <PolkadotConfig as Config>::Address {
+        self.0.address()
+    }
+
+    fn sign(&self, signer_payload: &[u8]) -> <PolkadotConfig as Config>::Signature {
+        self.0.sign(signer_payload)
+    }
+}
@@ -0,0 +1,161 @@
+//! Executing a call against the node's current state without including it
+//! in a block (`configure-all --dry-run --preview-events`), to see the
+//! events and dispatch result a real submission would produce.
+
+use anyhow::{Context, Result};
+use subxt::backend::legacy::rpc_methods::DryRunResult;
+use subxt::backend::legacy::LegacyRpcMethods;
+use subxt::dynamic::Value;
+use subxt::ext::scale_value::{At, ValueDef};
+use subxt::tx::DynamicPayload;
+use subxt::utils::AccountId32;
+use subxt::{Metadata, OnlineClient, PolkadotConfig};
+
+use crate::render;
+
+/// Runtime API that executes a call and reports its effects, events included
+const DRY_RUN_API: &str = "DryRunApi";
+
+/// XCM version for the results of `dry_run_call`'s three-argument form.
+/// We don't read the XCM it returns, but the runtime needs one it supports.
+const RESULT_XCM_VERSION: u32 = 4;
+
+/// What a call would do if submitted now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    /// Events as `Pallet(Event(fields))`; `None` when the node could only
+    /// report the dispatch result (`system_dryRun`)
+    pub events: Option<Vec<String>>,
+    /// Why dispatch would fail, e.g. `BadOrigin` or `Bridge.InvalidChain`
+    pub error: Option<String>,
+}
+
+/// Simulate `call` dispatched by `origin`. Runtimes with the `DryRunApi`
+/// report events as well as the result; others fall back to `system_dryRun`
+/// on `signed`, the encoded signed extrinsic, which reports the result only.
+pub async fn simulate(
+    client: &OnlineClient<PolkadotConfig>,
+    rpc: &LegacyRpcMethods<PolkadotConfig>,
+    call: &DynamicPayload,
+    origin: &AccountId32,
+    signed: &[u8],
+) -> Result<Simulation> {
+    let metadata = client.metadata();
+    let Some(inputs) = metadata.runtime_api_trait_by_name(DRY_RUN_API)
+        .and_then(|api| api.method_by_name("dry_run_call"))
+        .map(|method| method.inputs().len())
+    else {
+        return dry_run_extrinsic(rpc, &metadata, signed).await;
+    };
+
+    // `OriginCaller::system(RawOrigin::Signed(origin))`
+    let origin = Value::unnamed_variant("system", [Value::unnamed_variant("Signed", [Value::from_bytes(origin.0)])]);
+    let mut args = vec![origin, call.clone().into_value()];
+    if inputs == 3 {
+        args.push(Value::u128(RESULT_XCM_VERSION.into()));
+    }
+    let response = client.runtime_api().at_latest().await
+        .context("Failed to get the latest block for the dry run")?
+        .call(subxt::dynamic::runtime_api_call(DRY_RUN_API, "dry_run_call", args))
+        .await
+        .context("DryRunApi.dry_run_call failed")?
+        .to_value()
+        .context("Failed to decode the dry run result")?
+        .remove_context();
+    let (events, error) = effects(&response)?;
+    Ok(Simulation { events: Some(events), error: error.map(|error| dispatch_error(error, &metadata)) })
+}
+
+/// The events and any `DispatchError` in `Result<CallDryRunEffects, Error>`,
+/// as returned by `dry_run_call`
+fn effects(result: &Value) -> Result<(Vec<String>, Option<&Value>)> {
+    let ValueDef::Variant(result) = &result.value else {
+        anyhow::bail!("Unexpected dry run result {}", result);
+    };
+    if result.name != "Ok" {
+        let values = Value { value: ValueDef::Variant(result.clone()), context: () };
+        anyhow::bail!("The node could not dry-run the call: {}", render::value(&values));
+    }
+    let effects = result.at(0).context("Dry run result has no effects")?;
+
+    let events = match effects.at("emitted_events").map(|events| &events.value) {
+        Some(ValueDef::Composite(events)) => events.values().map(render::value).collect(),
+        _ => anyhow::bail!("Dry run effects have no emitted_events"),
+    };
+    let error = match &effects.at("execution_result").context("Dry run effects have no execution_result")?.value {
+        ValueDef::Variant(outcome) if outcome.name == "Err" => {
+            Some(outcome.at(0).and_then(|failure| failure.at("error")).context("Dry run failure has no error")?)
+        }
+        _ => None,
+    };
+    Ok((events, error))
+}
+
+/// A `DispatchError` value with module errors named, e.g. `Bridge.InvalidChain`
+fn dispatch_error(error: &Value, metadata: &Metadata) -> String {
+    let ValueDef::Variant(variant) = &error.value else {
+        return error.to_string();
+    };
+    if variant.name == "Module" {
+        let index = variant.at(0).at("index").and_then(Value::as_u128).and_then(|index| u8::try_from(index).ok());
+        let error_index = variant.at(0).at("error").at(0).and_then(Value::as_u128).and_then(|index| u8::try_from(index).ok());
+        if let (Some(index), Some(error_index)) = (index, error_index) {
+            if let Some(pallet) = metadata.pallet_by_index(index) {
+                if let Some(error) = pallet.error_variant_by_index(error_index) {
+                    return format!("{}.{}", pallet.name(), error.name);
+                }
+            }
+        }
+    }
+    render::value(error)
+}
+
+/// `system_dryRun` on a signed extrinsic: the dispatch result without events
+async fn dry_run_extrinsic(
+    rpc: &LegacyRpcMethods<PolkadotConfig>,
+    metadata: &Metadata,
+    signed: &[u8],
+) -> Result<Simulation> {
+    let result = rpc.dry_run(signed, None).await
+        .context("system_dryRun failed; the node may not expose unsafe RPC methods")?
+        .into_dry_run_result(metadata)
+        .context("Failed to decode the system_dryRun result")?;
+    let error = match result {
+        DryRunResult::Success => None,
+        DryRunResult::DispatchError(error) => Some(error.to_string()),
+        DryRunResult::TransactionValidityError => Some("the transaction is invalid (bad nonce, fee or signature)".to_string()),
+    };
+    Ok(Simulation { events: None, error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effects() {
+        let event = Value::unnamed_variant("Bridge", [Value::unnamed_variant("FeeSet", [Value::from_bytes("tron"), Value::u128(3_000)])]);
+        let ok = Value::unnamed_variant("Ok", [Value::named_composite([
+            ("execution_result", Value::unnamed_variant("Ok", [Value::unnamed_composite([])])),
+            ("emitted_events", Value::unnamed_composite([event])),
+        ])]);
+        let (events, error) = effects(&ok).unwrap();
+        assert_eq!(events, [r#"Bridge(FeeSet("tron", 3000))"#]);
+        assert!(error.is_none());
+
+        let failed = Value::unnamed_variant("Ok", [Value::named_composite([
+            ("execution_result", Value::unnamed_variant("Err", [Value::named_composite([
+                ("post_info", Value::unnamed_composite([])),
+                ("error", Value::unnamed_variant("BadOrigin", [])),
+            ])])),
+            ("emitted_events", Value::unnamed_composite([])),
+        ])]);
+        let (events, error) = effects(&failed).unwrap();
+        assert!(events.is_empty());
+        assert_eq!(error.map(render::value).as_deref(), Some("BadOrigin"));
+
+        let unsupported = Value::unnamed_variant("Err", [Value::unnamed_variant("Unimplemented", [])]);
+        let err = effects(&unsupported).unwrap_err();
+        assert_eq!(err.to_string(), "The node could not dry-run the call: Err(Unimplemented)");
+    }
+}
@@ -0,0 +1,85 @@
+//! `--output line`: one terse status line per chain for grep/awk pipelines.
+//!
+//! The format is stable across versions: `<chain> <STATUS>` followed by
+//! space-separated `key=value` pairs. STATUS is one of OK, PROPOSED, DRIFT,
+//! FAILED, NOT_EXECUTED or TIMED_OUT. Keys are never renamed or reordered,
+//! only appended; a key whose value is unknown is left out. Values with
+//! spaces, quotes or `=` are double-quoted with `\"` and `\\` escaped.
+
+use std::fmt;
+
+use crate::report::Receipt;
+
+/// One chain's status line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusLine {
+    chain: String,
+    status: &'static str,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl StatusLine {
+    pub fn new(chain: &str, status: &'static str) -> Self {
+        Self { chain: chain.to_string(), status, fields: Vec::new() }
+    }
+
+    /// Append `key=value`
+    pub fn field(mut self, key: &'static str, value: impl fmt::Display) -> Self {
+        self.fields.push((key, value.to_string()));
+        self
+    }
+
+    /// Append `key=value` if `value` is known
+    pub fn field_opt(self, key: &'static str, value: Option<impl fmt::Display>) -> Self {
+        match value {
+            Some(value) => self.field(key, value),
+            None => self,
+        }
+    }
+
+    /// Append the block, fee and nonce of a finalized call
+    pub fn receipt(self, receipt: Option<&Receipt>) -> Self {
+        self.field_opt("block", receipt.map(|receipt| receipt.block_number))
+            .field_opt("fee", receipt.and_then(|receipt| receipt.fee))
+            .field_opt("nonce", receipt.and_then(|receipt| receipt.nonce))
+    }
+}
+
+impl fmt::Display for StatusLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.chain, self.status)?;
+        for (key, value) in &self.fields {
+            write!(f, " {}={}", key, quote(value))?;
+        }
+        Ok(())
+    }
+}
+
+/// `value` as it appears after `key=`: bare unless it would split the line
+fn quote(value: &str) -> String {
+    if !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=' || c == '\\') {
+        return value.to_string();
+    }
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line() {
+        let receipt = Receipt { block_number: 12345, fee: None, nonce: Some(7), era: None };
+        let line = StatusLine::new("ethereum", "OK").receipt(Some(&receipt)).field("fee_percent", "0.01%");
+        assert_eq!(line.to_string(), "ethereum OK block=12345 nonce=7 fee_percent=0.01%");
+
+        assert_eq!(StatusLine::new("xrp", "NOT_EXECUTED").receipt(None).to_string(), "xrp NOT_EXECUTED");
+    }
+
+    #[test]
+    fn test_quote() {
+        let line = StatusLine::new("tron", "FAILED").field("error", "Extrinsic \"dropped\" at a=b");
+        assert_eq!(line.to_string(), r#"tron FAILED error="Extrinsic \"dropped\" at a=b""#);
+        assert_eq!(quote(""), "\"\"");
+    }
+}
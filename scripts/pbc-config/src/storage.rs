@@ -0,0 +1,986 @@
+//! Dynamic storage access and decoding for the bridge pallet.
+
+use anyhow::{Context, Result};
+use parity_scale_codec::Decode;
+use serde::Serialize;
+use std::future::Future;
+use subxt::backend::legacy::LegacyRpcMethods;
+use subxt::backend::rpc::{rpc_params, RpcClient};
+use subxt::config::substrate::BlakeTwo256;
+use subxt::config::Hasher;
+use subxt::dynamic::{At, DecodedValue, Value};
+use subxt::ext::scale_encode::EncodeAsType;
+use subxt::utils::{AccountId32, H256};
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::calls::BRIDGE_PALLET;
+use crate::{units, BridgeConfiguration, ChainConfig};
+
+/// The side of a bridge an amount is counted on, which decides its decimals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The external chain, e.g. what is locked
+    Source,
+    /// Etrid, e.g. what is minted
+    Dest,
+}
+
+/// Token mapping as stored by `Bridge::TokenMappings`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenMapping {
+    pub token_address: String,
+    pub exchange_rate: u128,
+    /// Source token decimals
+    pub decimals: u8,
+    pub dest_decimals: u8,
+}
+
+impl TokenMapping {
+    /// The mapping that configuring `chain_config` should produce on-chain
+    pub fn expected(chain_config: &ChainConfig) -> Result<Self> {
+        Ok(Self {
+            token_address: chain_config.token_address.clone(),
+            exchange_rate: chain_config.runtime_exchange_rate()?,
+            decimals: chain_config.source_decimals,
+            dest_decimals: chain_config.dest_decimals(),
+        })
+    }
+
+    /// Decimals of amounts counted on `side`
+    pub fn decimals_on(&self, side: Side) -> u8 {
+        match side {
+            Side::Source => self.decimals,
+            Side::Dest => self.dest_decimals,
+        }
+    }
+
+    /// `amount` base units counted on `side`, as whole tokens
+    pub fn format_amount(&self, amount: u128, side: Side) -> String {
+        units::format_units(amount, self.decimals_on(side))
+    }
+
+    /// `(field, value)` pairs in a stable order for display and diffing
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("token_address", self.token_address.clone()),
+            ("exchange_rate", self.exchange_rate.to_string()),
+            ("decimals", self.decimals.to_string()),
+            ("dest_decimals", self.dest_decimals.to_string()),
+        ]
+    }
+
+    /// The stored value for this mapping under `layout`; `None` when
+    /// version 0's single `decimals` can't hold differing dest decimals
+    fn to_value(&self, layout: StorageLayout) -> Option<Value> {
+        let mut fields = vec![
+            ("token_address", Value::from_bytes(&self.token_address)),
+            ("exchange_rate", Value::u128(self.exchange_rate)),
+            ("decimals", Value::u128(self.decimals as u128)),
+        ];
+        match layout {
+            StorageLayout::V0 if self.dest_decimals != self.decimals => return None,
+            StorageLayout::V0 => {}
+            StorageLayout::V1 => fields.push(("dest_decimals", Value::u128(self.dest_decimals as u128))),
+        }
+        Some(Value::named_composite(fields))
+    }
+
+    fn from_value(value: &DecodedValue, layout: StorageLayout) -> Result<Self> {
+        let token_address = bytes_field(value, "token_address")?;
+        let decimals: u8 = u128_field(value, "decimals")?
+            .try_into()
+            .context("Token mapping decimals out of range")?;
+        let dest_decimals = match layout {
+            // A runtime that added the field without bumping the storage
+            // version would otherwise have its dest decimals silently dropped
+            StorageLayout::V0 if value.at("dest_decimals").is_some() => anyhow::bail!(
+                "Token mapping has a `dest_decimals` field, but storage version 0 has none; \
+                 set the chain's storage_version or fix the pallet's storage version"
+            ),
+            StorageLayout::V0 => decimals,
+            StorageLayout::V1 => u128_field(value, "dest_decimals")?
+                .try_into()
+                .context("Token mapping dest_decimals out of range")?,
+        };
+        Ok(Self {
+            token_address: String::from_utf8_lossy(&token_address).into_owned(),
+            exchange_rate: u128_field(value, "exchange_rate")?,
+            decimals,
+            dest_decimals,
+        })
+    }
+}
+
+/// Newest Bridge pallet storage version this tool can decode
+pub const LATEST_STORAGE_VERSION: u16 = 1;
+
+/// Layout of `Bridge::TokenMappings` values, by pallet storage version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageLayout {
+    /// Version 0: one `decimals` field shared by both sides of the bridge
+    V0,
+    /// Version 1: separate `decimals` and `dest_decimals`
+    V1,
+}
+
+impl StorageLayout {
+    /// Pick the layout for the pallet's on-chain storage version, refusing
+    /// when it contradicts the `storage_version` configured for `chain`
+    pub fn select(chain: &str, on_chain: u16, configured: Option<u16>) -> Result<Self> {
+        if let Some(configured) = configured.filter(|configured| *configured != on_chain) {
+            anyhow::bail!(
+                "{} is configured with storage_version {} but the Bridge pallet reports {}; refusing to decode",
+                chain,
+                configured,
+                on_chain
+            );
+        }
+        match on_chain {
+            0 => Ok(Self::V0),
+            1 => Ok(Self::V1),
+            _ => anyhow::bail!(
+                "Bridge pallet storage version {} is newer than this tool supports (up to {})",
+                on_chain,
+                LATEST_STORAGE_VERSION
+            ),
+        }
+    }
+}
+
+/// The bridge state for one chain as reported by a single endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BridgeState {
+    pub token_mapping: Option<TokenMapping>,
+    pub fee_permill: Option<u32>,
+}
+
+impl BridgeState {
+    /// The state that configuring `chain_config` with `bridge_config` should produce
+    pub fn expected(chain_config: &ChainConfig, bridge_config: &BridgeConfiguration) -> Result<Self> {
+        Ok(Self {
+            token_mapping: Some(TokenMapping::expected(chain_config)?),
+            fee_permill: Some(crate::units::percent_to_permill(&bridge_config.bridge_fee_percent)
+                .context("Invalid bridge_fee_percent")?),
+        })
+    }
+
+    /// Flattened `(field, value)` pairs, with `None` for unset entries
+    pub fn fields(&self) -> Vec<(&'static str, Option<String>)> {
+        let mut fields: Vec<(&'static str, Option<String>)> = match &self.token_mapping {
+            Some(mapping) => mapping.fields().into_iter().map(|(field, value)| (field, Some(value))).collect(),
+            None => ["token_address", "exchange_rate", "decimals", "dest_decimals"].into_iter().map(|field| (field, None)).collect(),
+        };
+        fields.push(("fee", self.fee_permill.map(crate::units::permill_to_percent)));
+        fields
+    }
+}
+
+/// Storage as of one block
+type BlockStorage = subxt::storage::Storage<PolkadotConfig, OnlineClient<PolkadotConfig>>;
+
+async fn latest(client: &OnlineClient<PolkadotConfig>) -> Result<BlockStorage> {
+    client.storage().at_latest().await.context("Failed to get latest block")
+}
+
+/// Fetch everything we know how to decode about `chain`'s bridge
+pub async fn fetch_bridge_state(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+    layout: StorageLayout,
+) -> Result<BridgeState> {
+    Ok(BridgeState {
+        token_mapping: fetch_token_mapping(client, chain, layout).await?,
+        fee_permill: fetch_bridge_fee(client, chain).await?,
+    })
+}
+
+/// `chain`'s bridge state and confirmation depth as of block `at`, decoding
+/// with the storage version the pallet had then. The node must still have
+/// that block's state (an archive node, for anything but recent blocks).
+pub async fn fetch_bridge_state_at(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+    storage_version: Option<u16>,
+    at: H256,
+) -> Result<(BridgeState, Option<u32>)> {
+    let storage = client.storage().at(at);
+    let layout = StorageLayout::select(chain, storage_version_in(&storage).await?, storage_version)?;
+    let state = BridgeState {
+        token_mapping: token_mapping_in(&storage, chain, layout).await?,
+        fee_permill: bridge_fee_in(&storage, chain).await?,
+    };
+    Ok((state, confirmations_in(&storage, chain).await?))
+}
+
+/// Whether `chain`'s on-chain state matches `expected`, comparing only the
+/// node's `state_getStorageHash` for each entry against the hash of the
+/// locally encoded value. Much cheaper than fetching and decoding values,
+/// but says nothing about *what* differs. The mapping is encoded in `layout`.
+pub async fn matches_by_hash(
+    client: &OnlineClient<PolkadotConfig>,
+    rpc: &RpcClient,
+    chain: &str,
+    expected: &BridgeState,
+    layout: StorageLayout,
+) -> Result<bool> {
+    let token_mapping = match &expected.token_mapping {
+        Some(mapping) => match mapping.to_value(layout) {
+            Some(value) => Some(value),
+            // Nothing stored in this layout can match
+            None => return Ok(false),
+        },
+        None => None,
+    };
+    let fee = expected.fee_permill.map(|permill| Value::unnamed_composite([Value::u128(permill as u128)]));
+
+    let at = client.blocks().at_latest().await.context("Failed to get latest block")?.hash();
+    for (entry, value) in [("TokenMappings", token_mapping), ("BridgeFees", fee)] {
+        let address = subxt::dynamic::storage(BRIDGE_PALLET, entry, vec![Value::from_bytes(chain)]);
+        let key = client.storage().address_bytes(&address)
+            .context(format!("Bridge::{} is not available on this runtime", entry))?;
+        let expected_bytes = value.map(|value| encode_entry_value(client, entry, &value)).transpose()?;
+
+        let remote: Option<H256> = rpc
+            .request("state_getStorageHash", rpc_params![format!("0x{}", hex::encode(&key)), at])
+            .await
+            .context(format!("Failed to fetch storage hash of Bridge::{} for {}", entry, chain))?;
+        if !hash_matches(expected_bytes.as_deref(), remote) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// SCALE-encode `value` as the runtime's value type for `Bridge::<entry>`
+fn encode_entry_value(client: &OnlineClient<PolkadotConfig>, entry: &str, value: &Value) -> Result<Vec<u8>> {
+    let metadata = client.metadata();
+    let type_id = metadata.pallet_by_name(BRIDGE_PALLET)
+        .and_then(|pallet| pallet.storage())
+        .and_then(|storage| storage.entry_by_name(entry))
+        .context(format!("Bridge::{} is not available on this runtime", entry))?
+        .entry_type()
+        .value_ty();
+    value.encode_as_type(&type_id, metadata.types())
+        .context(format!("Expected value does not fit the runtime's Bridge::{} type", entry))
+}
+
+fn hash_matches(expected: Option<&[u8]>, remote: Option<H256>) -> bool {
+    expected.map(BlakeTwo256::hash) == remote
+}
+
+/// Fetch the Bridge pallet's `StorageVersion`; FRAME treats an unset one as 0
+pub async fn fetch_storage_version(client: &OnlineClient<PolkadotConfig>) -> Result<u16> {
+    storage_version_in(&latest(client).await?).await
+}
+
+async fn storage_version_in(storage: &BlockStorage) -> Result<u16> {
+    let key = [
+        sp_crypto_hashing::twox_128(BRIDGE_PALLET.as_bytes()),
+        sp_crypto_hashing::twox_128(b":__STORAGE_VERSION__:"),
+    ].concat();
+
+    let value = storage
+        .fetch_raw(key)
+        .await
+        .context("Failed to fetch Bridge storage version")?;
+
+    match value {
+        Some(bytes) => u16::decode(&mut &bytes[..]).context("Failed to decode Bridge storage version"),
+        None => Ok(0),
+    }
+}
+
+/// Fetch the token mapping for `chain` at the latest block, if one exists,
+/// decoding it with `layout`
+pub async fn fetch_token_mapping(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+    layout: StorageLayout,
+) -> Result<Option<TokenMapping>> {
+    token_mapping_in(&latest(client).await?, chain, layout).await
+}
+
+async fn token_mapping_in(
+    storage: &BlockStorage,
+    chain: &str,
+    layout: StorageLayout,
+) -> Result<Option<TokenMapping>> {
+    let address = subxt::dynamic::storage(
+        BRIDGE_PALLET,
+        "TokenMappings",
+        vec![Value::from_bytes(chain)],
+    );
+
+    let value = storage
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch token mapping for {}", chain))?;
+
+    match value {
+        Some(value) => {
+            let value = value.to_value().context("Failed to decode token mapping")?;
+            Ok(Some(TokenMapping::from_value(&value, layout)?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Pallet that publishes exchange rates for oracle-priced bridges
+pub const ORACLE_PALLET: &str = "Oracle";
+
+/// Fetch the oracle's current rate for `chain` (source units per dest unit, as
+/// the bridge stores it) at the latest block, if it has one
+pub async fn fetch_oracle_rate(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+) -> Result<Option<u128>> {
+    let address = subxt::dynamic::storage(
+        ORACLE_PALLET,
+        "Rates",
+        vec![Value::from_bytes(chain)],
+    );
+
+    let value = client.storage()
+        .at_latest()
+        .await
+        .context("Failed to get latest block")?
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch oracle rate for {}", chain))?;
+
+    match value {
+        Some(value) => Ok(Some(value.as_type::<u128>().context("Failed to decode oracle rate")?)),
+        None => Ok(None),
+    }
+}
+
+/// Fetch the confirmation depth stored for `chain` at the latest block, if set
+pub async fn fetch_confirmations(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+) -> Result<Option<u32>> {
+    confirmations_in(&latest(client).await?, chain).await
+}
+
+/// Fetch how many relayers must attest `chain`'s transfers at the latest block, if set
+pub async fn fetch_relayer_threshold(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+) -> Result<Option<u32>> {
+    let address = subxt::dynamic::storage(
+        BRIDGE_PALLET,
+        "RelayerThresholds",
+        vec![Value::from_bytes(chain)],
+    );
+
+    let value = latest(client).await?
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch relayer threshold for {}", chain))?;
+
+    match value {
+        Some(value) => Ok(Some(value.as_type::<u32>().context("Failed to decode relayer threshold")?)),
+        None => Ok(None),
+    }
+}
+
+/// Fetch the withdrawal confirmation depth stored for `chain` at the latest
+/// block, if one was set apart from the deposit depth
+pub async fn fetch_withdrawal_confirmations(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+) -> Result<Option<u32>> {
+    let address = subxt::dynamic::storage(
+        BRIDGE_PALLET,
+        "WithdrawalConfirmations",
+        vec![Value::from_bytes(chain)],
+    );
+
+    let value = latest(client).await?
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch withdrawal confirmations for {}", chain))?;
+
+    match value {
+        Some(value) => Ok(Some(value.as_type::<u32>().context("Failed to decode withdrawal confirmations")?)),
+        None => Ok(None),
+    }
+}
+
+async fn confirmations_in(storage: &BlockStorage, chain: &str) -> Result<Option<u32>> {
+    let address = subxt::dynamic::storage(
+        BRIDGE_PALLET,
+        "Confirmations",
+        vec![Value::from_bytes(chain)],
+    );
+
+    let value = storage
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch confirmations for {}", chain))?;
+
+    match value {
+        Some(value) => Ok(Some(value.as_type::<u32>().context("Failed to decode confirmations")?)),
+        None => Ok(None),
+    }
+}
+
+/// Fetch the bridge fee for `chain` as a `Permill`, if one is set
+pub async fn fetch_bridge_fee(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+) -> Result<Option<u32>> {
+    bridge_fee_in(&latest(client).await?, chain).await
+}
+
+async fn bridge_fee_in(storage: &BlockStorage, chain: &str) -> Result<Option<u32>> {
+    let address = subxt::dynamic::storage(
+        BRIDGE_PALLET,
+        "BridgeFees",
+        vec![Value::from_bytes(chain)],
+    );
+
+    let value = storage
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch bridge fee for {}", chain))?;
+
+    match value {
+        Some(value) => {
+            let value = value.to_value().context("Failed to decode bridge fee")?;
+            Ok(Some(permill_value(&value)?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Whether `account` is registered in `Bridge::Relayers` for `chain`
+pub async fn is_relayer(client: &OnlineClient<PolkadotConfig>, chain: &str, account: &AccountId32) -> Result<bool> {
+    let address = subxt::dynamic::storage(
+        BRIDGE_PALLET,
+        "Relayers",
+        vec![Value::from_bytes(chain), Value::from_bytes(account.0)],
+    );
+    let key = client.storage()
+        .address_bytes(&address)
+        .context("Bridge::Relayers is not available on this runtime")?;
+
+    let value = client.storage()
+        .at_latest()
+        .await
+        .context("Failed to get latest block")?
+        .fetch_raw(key)
+        .await
+        .context(format!("Failed to fetch relayer {} for {}", account, chain))?;
+    Ok(value.is_some())
+}
+
+/// Storage entry of each chain's maximum transfer amount, set by `set_max_amount`
+pub const MAX_AMOUNTS: &str = "MaxAmounts";
+
+/// Storage entry of each chain's minimum transfer amount, set by `set_min_amount`
+pub const MIN_AMOUNTS: &str = "MinAmounts";
+
+/// Fetch `chain`'s transfer limit from `entry` (`MAX_AMOUNTS` or `MIN_AMOUNTS`);
+/// `None` when unset or when the runtime keeps no such entry
+pub async fn fetch_transfer_limit(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+    entry: &str,
+) -> Result<Option<u128>> {
+    let tracked = client.metadata()
+        .pallet_by_name(BRIDGE_PALLET)
+        .and_then(|pallet| pallet.storage())
+        .is_some_and(|storage| storage.entry_by_name(entry).is_some());
+    if !tracked {
+        return Ok(None);
+    }
+
+    let address = subxt::dynamic::storage(BRIDGE_PALLET, entry, vec![Value::from_bytes(chain)]);
+    let value = latest(client).await?
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch {} for {}", entry, chain))?;
+
+    match value {
+        Some(value) => Ok(Some(value.as_type::<u128>().context(format!("Failed to decode {}", entry))?)),
+        None => Ok(None),
+    }
+}
+
+/// Whether the runtime records relayer liveness in `Bridge::RelayerHeartbeats`
+pub fn tracks_heartbeats(client: &OnlineClient<PolkadotConfig>) -> bool {
+    client.metadata()
+        .pallet_by_name(BRIDGE_PALLET)
+        .and_then(|pallet| pallet.storage())
+        .is_some_and(|storage| storage.entry_by_name("RelayerHeartbeats").is_some())
+}
+
+/// Block of `relayer`'s last heartbeat for `chain`, if it has sent one
+pub async fn fetch_relayer_heartbeat(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+    relayer: &AccountId32,
+) -> Result<Option<u64>> {
+    let address = subxt::dynamic::storage(
+        BRIDGE_PALLET,
+        "RelayerHeartbeats",
+        vec![Value::from_bytes(chain), Value::from_bytes(relayer.0)],
+    );
+
+    let value = latest(client).await?
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch heartbeat of relayer {} for {}", relayer, chain))?;
+
+    match value {
+        Some(value) => {
+            let block = value.to_value()
+                .context("Failed to decode relayer heartbeat")?
+                .as_u128()
+                .context("Relayer heartbeat is not a block number")?;
+            Ok(Some(u64::try_from(block).context("Relayer heartbeat block number out of range")?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// `relayer`'s weight for `chain` from `Bridge::RelayerWeights`, or `None`
+/// when the runtime doesn't weight relayers or this one has no weight set
+pub async fn fetch_relayer_weight(
+    client: &OnlineClient<PolkadotConfig>,
+    chain: &str,
+    relayer: &AccountId32,
+) -> Result<Option<u32>> {
+    let has_weights = client.metadata()
+        .pallet_by_name(BRIDGE_PALLET)
+        .and_then(|pallet| pallet.storage())
+        .is_some_and(|storage| storage.entry_by_name("RelayerWeights").is_some());
+    if !has_weights {
+        return Ok(None);
+    }
+
+    let address = subxt::dynamic::storage(
+        BRIDGE_PALLET,
+        "RelayerWeights",
+        vec![Value::from_bytes(chain), Value::from_bytes(relayer.0)],
+    );
+    let value = client.storage()
+        .at_latest()
+        .await
+        .context("Failed to get latest block")?
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch weight of relayer {} for {}", relayer, chain))?;
+
+    match value {
+        Some(value) => Ok(Some(value.as_type::<u32>().context("Failed to decode relayer weight")?)),
+        None => Ok(None),
+    }
+}
+
+/// How much of a chain's token the bridge accounts for, in base units:
+/// locked on the source chain and minted on the destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Supply {
+    pub locked: u128,
+    pub minted: u128,
+}
+
+impl Supply {
+    /// What `locked` should have minted at `exchange_rate` source units per destination unit
+    pub fn expected_minted(&self, exchange_rate: u128) -> u128 {
+        self.locked / exchange_rate.max(1)
+    }
+
+    /// How far `minted` is from [`Supply::expected_minted`], as a percent of it
+    pub fn divergence_percent(&self, exchange_rate: u128) -> f64 {
+        let expected = self.expected_minted(exchange_rate);
+        let difference = self.minted.abs_diff(expected);
+        match (expected, difference) {
+            (_, 0) => 0.0,
+            (0, _) => f64::INFINITY,
+            _ => difference as f64 / expected as f64 * 100.0,
+        }
+    }
+}
+
+/// A [`Supply`] as whole tokens, each total in its own side's decimals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormattedSupply {
+    pub locked: String,
+    pub minted: String,
+    pub expected_minted: String,
+}
+
+impl Supply {
+    /// Locked in `mapping`'s source decimals; minted and what locked should
+    /// have minted in its dest decimals
+    pub fn formatted(&self, mapping: &TokenMapping) -> FormattedSupply {
+        FormattedSupply {
+            locked: mapping.format_amount(self.locked, Side::Source),
+            minted: mapping.format_amount(self.minted, Side::Dest),
+            expected_minted: mapping.format_amount(self.expected_minted(mapping.exchange_rate), Side::Dest),
+        }
+    }
+}
+
+/// `chain`'s `Bridge::TotalLocked` and `Bridge::TotalMinted`, zero where unset
+pub async fn fetch_supply(client: &OnlineClient<PolkadotConfig>, chain: &str) -> Result<Supply> {
+    Ok(Supply {
+        locked: fetch_total(client, "TotalLocked", chain).await?,
+        minted: fetch_total(client, "TotalMinted", chain).await?,
+    })
+}
+
+async fn fetch_total(client: &OnlineClient<PolkadotConfig>, entry: &str, chain: &str) -> Result<u128> {
+    let has_entry = client.metadata()
+        .pallet_by_name(BRIDGE_PALLET)
+        .and_then(|pallet| pallet.storage())
+        .is_some_and(|storage| storage.entry_by_name(entry).is_some());
+    if !has_entry {
+        anyhow::bail!("Runtime has no {}::{} storage to read the supply from", BRIDGE_PALLET, entry);
+    }
+
+    let address = subxt::dynamic::storage(BRIDGE_PALLET, entry, vec![Value::from_bytes(chain)]);
+    let value = client.storage()
+        .at_latest()
+        .await
+        .context("Failed to get latest block")?
+        .fetch(&address)
+        .await
+        .context(format!("Failed to fetch {} for {}", entry, chain))?;
+
+    match value {
+        Some(value) => value.as_type::<u128>().context(format!("Failed to decode {}", entry)),
+        None => Ok(0),
+    }
+}
+
+/// The bridge operator from `Bridge::Operator`, or `None` when the runtime
+/// doesn't have that entry or it is unset
+pub async fn fetch_operator(client: &OnlineClient<PolkadotConfig>) -> Result<Option<AccountId32>> {
+    let has_operator = client.metadata()
+        .pallet_by_name(BRIDGE_PALLET)
+        .and_then(|pallet| pallet.storage())
+        .is_some_and(|storage| storage.entry_by_name("Operator").is_some());
+    if !has_operator {
+        return Ok(None);
+    }
+
+    let address = subxt::dynamic::storage(BRIDGE_PALLET, "Operator", Vec::<Value>::new());
+    let value = client.storage()
+        .at_latest()
+        .await
+        .context("Failed to get latest block")?
+        .fetch(&address)
+        .await
+        .context("Failed to fetch bridge operator")?;
+
+    match value {
+        Some(value) => Ok(Some(value.as_type::<AccountId32>().context("Failed to decode bridge operator")?)),
+        None => Ok(None),
+    }
+}
+
+/// Fetch the account in `Sudo::Key`, if the runtime has one set
+pub async fn fetch_sudo_key(client: &OnlineClient<PolkadotConfig>) -> Result<Option<AccountId32>> {
+    let address = subxt::dynamic::storage(crate::calls::SUDO_PALLET, "Key", Vec::<Value>::new());
+
+    let value = client.storage()
+        .at_latest()
+        .await
+        .context("Failed to get latest block")?
+        .fetch(&address)
+        .await
+        .context("Failed to fetch sudo key")?;
+
+    match value {
+        Some(value) => Ok(Some(value.as_type::<AccountId32>().context("Failed to decode sudo key")?)),
+        None => Ok(None),
+    }
+}
+
+/// Relayer keys requested per `state_getKeysPaged` call
+pub const RELAYER_PAGE_SIZE: u32 = 100;
+
+/// Walk `Bridge::Relayers(chain, account)` one page of keys at a time,
+/// calling `on_relayer` as each page arrives. Returns the total count.
+///
+/// Every page is read at the same block so the set can't shift mid-walk.
+pub async fn for_each_relayer(
+    client: &OnlineClient<PolkadotConfig>,
+    rpc: &LegacyRpcMethods<PolkadotConfig>,
+    chain: &str,
+    page_size: u32,
+    mut on_relayer: impl FnMut(AccountId32),
+) -> Result<usize> {
+    let address = subxt::dynamic::storage(
+        BRIDGE_PALLET,
+        "Relayers",
+        vec![Value::from_bytes(chain)],
+    );
+    let prefix = client.storage()
+        .address_bytes(&address)
+        .context("Bridge::Relayers is not available on this runtime")?;
+    let at = client.blocks()
+        .at_latest()
+        .await
+        .context("Failed to get latest block")?
+        .hash();
+
+    paginate_keys(
+        page_size,
+        |start_key| {
+            let prefix = prefix.clone();
+            async move {
+                rpc.state_get_keys_paged(&prefix, page_size, start_key.as_deref(), Some(at))
+                    .await
+                    .context(format!("Failed to fetch relayers for {}", chain))
+            }
+        },
+        |key| {
+            on_relayer(relayer_from_key(key, prefix.len())?);
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// Drive a paged key listing: each page starts after the last key of the
+/// previous one, and a short page means the listing is exhausted.
+async fn paginate_keys<F, Fut>(
+    page_size: u32,
+    mut fetch_page: F,
+    mut on_key: impl FnMut(&[u8]) -> Result<()>,
+) -> Result<usize>
+where
+    F: FnMut(Option<Vec<u8>>) -> Fut,
+    Fut: Future<Output = Result<Vec<Vec<u8>>>>,
+{
+    let mut start_key = None;
+    let mut total = 0;
+    loop {
+        let page = fetch_page(start_key.take()).await?;
+        for key in &page {
+            on_key(key)?;
+        }
+        total += page.len();
+        if page.len() < page_size as usize {
+            return Ok(total);
+        }
+        start_key = page.last().cloned();
+    }
+}
+
+/// The relayer is hashed with a `*Concat` hasher, so its raw account ends the key
+fn relayer_from_key(key: &[u8], prefix_len: usize) -> Result<AccountId32> {
+    if key.len() < prefix_len + 32 {
+        anyhow::bail!("Relayer storage key is too short ({} bytes)", key.len());
+    }
+    let account: [u8; 32] = key[key.len() - 32..].try_into()?;
+    Ok(AccountId32(account))
+}
+
+/// `Permill` decodes as a newtype around its parts-per-million `u32`
+fn permill_value(value: &DecodedValue) -> Result<u32> {
+    value.as_u128()
+        .or_else(|| value.at(0).and_then(|inner| inner.as_u128()))
+        .and_then(|parts| u32::try_from(parts).ok())
+        .context("Bridge fee is not a Permill")
+}
+
+fn u128_field(value: &DecodedValue, name: &str) -> Result<u128> {
+    value.at(name)
+        .and_then(|field| field.as_u128())
+        .context(format!("Missing or non-numeric field `{}`", name))
+}
+
+fn bytes_field(value: &DecodedValue, name: &str) -> Result<Vec<u8>> {
+    let field = value.at(name).context(format!("Missing field `{}`", name))?;
+    let mut bytes = Vec::new();
+    let mut index = 0;
+    while let Some(byte) = field.at(index) {
+        let byte = byte.as_u128()
+            .and_then(|b| u8::try_from(b).ok())
+            .context(format!("Field `{}` is not a byte sequence", name))?;
+        bytes.push(byte);
+        index += 1;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping_value(token_address: &str, exchange_rate: u128, decimals: u8) -> DecodedValue {
+        Value::named_composite([
+            ("token_address", Value::from_bytes(token_address)),
+            ("exchange_rate", Value::u128(exchange_rate)),
+            ("decimals", Value::u128(decimals as u128)),
+            ("dest_decimals", Value::u128(decimals as u128)),
+        ])
+        .map_context(|_| 0u32)
+    }
+
+    #[test]
+    fn test_supply_divergence() {
+        let supply = Supply { locked: 5_000_000_000, minted: 5 };
+        assert_eq!(supply.expected_minted(1_000_000_000), 5);
+        assert_eq!(supply.divergence_percent(1_000_000_000), 0.0);
+
+        let short = Supply { minted: 4, ..supply };
+        assert_eq!(short.divergence_percent(1_000_000_000), 20.0);
+        // Minted with nothing locked behind it
+        assert!(Supply { locked: 0, minted: 1 }.divergence_percent(1).is_infinite());
+    }
+
+    #[test]
+    fn test_formatted_supply() {
+        // 6 decimals locked, 18 minted. At rate 1 each source base unit backs
+        // one dest base unit, so 2.5 locked tokens back only 2.5e-12 minted ones.
+        let mapping = TokenMapping {
+            token_address: "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t".to_string(),
+            exchange_rate: 1,
+            decimals: 6,
+            dest_decimals: 18,
+        };
+        let supply = Supply { locked: 2_500_000, minted: 2_400_000_000_000_000_000 };
+        assert_eq!(supply.formatted(&mapping), FormattedSupply {
+            locked: "2.5".to_string(),
+            minted: "2.4".to_string(),
+            expected_minted: "0.0000000000025".to_string(),
+        });
+        assert_eq!(mapping.decimals_on(Side::Source), 6);
+        assert_eq!(mapping.format_amount(1, Side::Dest), "0.000000000000000001");
+    }
+
+    #[test]
+    fn test_decode_token_mapping() {
+        let value = mapping_value("0xdAC17F958D2ee523a2206206994597C13D831ec7", 10u128.pow(18), 18);
+        let mapping = TokenMapping::from_value(&value, StorageLayout::V1).unwrap();
+        assert_eq!(mapping, TokenMapping {
+            token_address: "0xdAC17F958D2ee523a2206206994597C13D831ec7".to_string(),
+            exchange_rate: 10u128.pow(18),
+            decimals: 18,
+            dest_decimals: 18,
+        });
+    }
+
+    #[test]
+    fn test_decode_storage_layouts() {
+        let v0 = Value::named_composite([
+            ("token_address", Value::from_bytes("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t")),
+            ("exchange_rate", Value::u128(1_000_000)),
+            ("decimals", Value::u128(6)),
+        ])
+        .map_context(|_| 0u32);
+        let v1 = Value::named_composite([
+            ("token_address", Value::from_bytes("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t")),
+            ("exchange_rate", Value::u128(1_000_000)),
+            ("decimals", Value::u128(6)),
+            ("dest_decimals", Value::u128(12)),
+        ])
+        .map_context(|_| 0u32);
+
+        let legacy = TokenMapping::from_value(&v0, StorageLayout::V0).unwrap();
+        assert_eq!((legacy.decimals, legacy.dest_decimals), (6, 6));
+        let current = TokenMapping::from_value(&v1, StorageLayout::V1).unwrap();
+        assert_eq!((current.decimals, current.dest_decimals), (6, 12));
+
+        // The current layout can't read a legacy value
+        let err = TokenMapping::from_value(&v0, StorageLayout::V1).unwrap_err();
+        assert!(err.to_string().contains("dest_decimals"), "{}", err);
+        // Nor the legacy layout a current value
+        let err = TokenMapping::from_value(&v1, StorageLayout::V0).unwrap_err();
+        assert!(err.to_string().contains("storage version 0 has none"), "{}", err);
+    }
+
+    #[test]
+    fn test_token_mapping_value_follows_layout() {
+        let mut mapping = TokenMapping {
+            token_address: "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t".to_string(),
+            exchange_rate: 1_000_000,
+            decimals: 6,
+            dest_decimals: 6,
+        };
+        for layout in [StorageLayout::V0, StorageLayout::V1] {
+            let value = mapping.to_value(layout).unwrap().map_context(|_| 0u32);
+            assert_eq!(value.at("dest_decimals").is_some(), layout == StorageLayout::V1);
+            assert_eq!(TokenMapping::from_value(&value, layout).unwrap(), mapping);
+        }
+
+        mapping.dest_decimals = 12;
+        assert!(mapping.to_value(StorageLayout::V0).is_none());
+        assert!(mapping.to_value(StorageLayout::V1).is_some());
+    }
+
+    #[test]
+    fn test_select_storage_layout() {
+        assert_eq!(StorageLayout::select("tron", 0, None).unwrap(), StorageLayout::V0);
+        assert_eq!(StorageLayout::select("tron", 1, Some(1)).unwrap(), StorageLayout::V1);
+
+        let err = StorageLayout::select("tron", 1, Some(0)).unwrap_err();
+        assert!(err.to_string().contains("storage_version 0 but the Bridge pallet reports 1"), "{}", err);
+        let err = StorageLayout::select("tron", 2, None).unwrap_err();
+        assert!(err.to_string().contains("newer than this tool supports"), "{}", err);
+    }
+
+    #[test]
+    fn test_decode_permill() {
+        let value = Value::unnamed_composite([Value::u128(3_000)]).map_context(|_| 0u32);
+        assert_eq!(permill_value(&value).unwrap(), 3_000);
+        let value = Value::u128(1_000).map_context(|_| 0u32);
+        assert_eq!(permill_value(&value).unwrap(), 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_large_relayer_set() {
+        let prefix = vec![0xAA; 48];
+        let keys: Vec<Vec<u8>> = (0..250u8)
+            .map(|i| [prefix.clone(), vec![0u8; 16], vec![i; 32]].concat())
+            .collect();
+
+        let mut requests = 0;
+        let mut relayers = Vec::new();
+        let total = paginate_keys(
+            100,
+            |start_key| {
+                requests += 1;
+                let from = match start_key {
+                    Some(start) => keys.iter().position(|key| *key == start).unwrap() + 1,
+                    None => 0,
+                };
+                let page = keys[from..].iter().take(100).cloned().collect();
+                async move { Ok(page) }
+            },
+            |key| {
+                relayers.push(relayer_from_key(key, prefix.len())?);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(total, 250);
+        assert_eq!(requests, 3);
+        assert_eq!(relayers[0], AccountId32([0; 32]));
+        assert_eq!(relayers[249], AccountId32([249; 32]));
+    }
+
+    #[test]
+    fn test_hash_matches() {
+        let encoded = [1u8, 2, 3];
+        assert!(hash_matches(Some(&encoded), Some(BlakeTwo256::hash(&encoded))));
+        assert!(!hash_matches(Some(&encoded), Some(BlakeTwo256::hash(&[1, 2]))));
+        assert!(!hash_matches(Some(&encoded), None));
+        assert!(hash_matches(None, None));
+    }
+
+    #[test]
+    fn test_decode_token_mapping_missing_field() {
+        let value = Value::named_composite([("exchange_rate", Value::u128(1))]).map_context(|_| 0u32);
+        assert!(TokenMapping::from_value(&value, StorageLayout::V1).is_err());
+    }
+}
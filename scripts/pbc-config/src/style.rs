@@ -0,0 +1,84 @@
+//! Terminal colors and tables for human-readable output.
+//!
+//! Styling goes through `if_supports_color`, so it is dropped automatically
+//! when the stream is not a terminal or `NO_COLOR` is set. JSON output and
+//! files never pass through here.
+
+use comfy_table::{presets, ContentArrangement, Table};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{ColorChoice, TableStyle};
+
+/// Apply `--color`; `auto` leaves detection to the terminal and `NO_COLOR`
+pub fn init(choice: ColorChoice) {
+    match choice {
+        ColorChoice::Auto => owo_colors::unset_override(),
+        ColorChoice::Always => owo_colors::set_override(true),
+        ColorChoice::Never => owo_colors::set_override(false),
+    }
+}
+
+/// Render a mismatch or divergence in red
+pub fn mismatch(stream: Stream, text: &str) -> String {
+    text.if_supports_color(stream, |text| text.red()).to_string()
+}
+
+/// Render a matching value in green
+pub fn matched(stream: Stream, text: &str) -> String {
+    text.if_supports_color(stream, |text| text.green()).to_string()
+}
+
+/// Render a banner on stderr that must not be missed, e.g. for a rehearsal
+pub fn banner(text: &str) -> String {
+    let text = format!("==== {} ====", text);
+    text.if_supports_color(Stream::Stderr, |text| text.bold().yellow().to_string()).to_string()
+}
+
+/// Render `rows` under `header` in the `--table-style` layout
+pub fn table(style: TableStyle, header: &[&str], rows: Vec<Vec<String>>) -> String {
+    let preset = match style {
+        TableStyle::Ascii => presets::ASCII_FULL,
+        TableStyle::Markdown => presets::ASCII_MARKDOWN,
+        TableStyle::Plain => presets::NOTHING,
+    };
+    let mut table = Table::new();
+    table.load_preset(preset)
+        .set_content_arrangement(ContentArrangement::Disabled)
+        .set_header(header.to_vec())
+        .add_rows(rows);
+
+    let rendered = table.to_string();
+    match style {
+        // Drop the cell padding at the line ends so columns split cleanly on whitespace
+        TableStyle::Plain => rendered.lines().map(str::trim).collect::<Vec<_>>().join("\n"),
+        TableStyle::Ascii | TableStyle::Markdown => rendered,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<Vec<String>> {
+        vec![vec!["solana".to_string(), "ok".to_string()], vec!["tron".to_string(), "drift".to_string()]]
+    }
+
+    #[test]
+    fn test_table_styles() {
+        assert_eq!(table(TableStyle::Plain, &["CHAIN", "STATUS"], rows()), [
+            "CHAIN   STATUS",
+            "solana  ok",
+            "tron    drift",
+        ].join("\n"));
+
+        assert_eq!(table(TableStyle::Markdown, &["CHAIN", "STATUS"], rows()), [
+            "| CHAIN  | STATUS |",
+            "|--------|--------|",
+            "| solana | ok     |",
+            "| tron   | drift  |",
+        ].join("\n"));
+
+        let ascii = table(TableStyle::Ascii, &["CHAIN", "STATUS"], rows());
+        assert!(ascii.starts_with("+--------+--------+"), "{}", ascii);
+    }
+}
@@ -0,0 +1,71 @@
+//! Prometheus metrics for long-running modes such as `watch`.
+//!
+//! Recording is a no-op unless `--metrics-addr` installed the exporter.
+
+use anyhow::{Context, Result};
+use metrics::{counter, describe_counter, describe_gauge, gauge};
+use metrics_exporter_prometheus::PrometheusBuilder;
+use std::net::SocketAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DRIFT_FIELDS: &str = "pbc_config_drift_fields";
+const LAST_VERIFY: &str = "pbc_config_last_successful_verify_timestamp_seconds";
+const BRIDGE_EVENTS: &str = "pbc_config_bridge_events_total";
+const RECONNECTS: &str = "pbc_config_reconnects_total";
+
+/// Serve metrics on `addr` from a background task
+pub fn install(addr: SocketAddr) -> Result<()> {
+    PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .install()
+        .context(format!("Failed to start metrics endpoint on {}", addr))?;
+    describe();
+    Ok(())
+}
+
+fn describe() {
+    describe_gauge!(DRIFT_FIELDS, "Fields whose live value differs from the config, per chain");
+    describe_gauge!(LAST_VERIFY, "Unix time of the last verify that found no drift, per chain");
+    describe_counter!(BRIDGE_EVENTS, "Bridge pallet events observed in finalized blocks");
+    describe_counter!(RECONNECTS, "Reconnects to the node after a failed iteration");
+}
+
+pub fn record_verify(chain: &str, drift_fields: usize) {
+    gauge!(DRIFT_FIELDS, "chain" => chain.to_string()).set(drift_fields as f64);
+    if drift_fields == 0 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        gauge!(LAST_VERIFY, "chain" => chain.to_string()).set(now.as_secs_f64());
+    }
+}
+
+pub fn record_events(count: usize) {
+    counter!(BRIDGE_EVENTS).increment(count as u64);
+}
+
+pub fn record_reconnect() {
+    counter!(RECONNECTS).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            record_verify("tron", 2);
+            record_verify("solana", 0);
+            record_events(3);
+            record_reconnect();
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains("pbc_config_drift_fields{chain=\"tron\"} 2"), "{}", rendered);
+        assert!(rendered.contains("pbc_config_last_successful_verify_timestamp_seconds{chain=\"solana\"}"));
+        assert!(!rendered.contains("pbc_config_last_successful_verify_timestamp_seconds{chain=\"tron\"}"));
+        assert!(rendered.contains("pbc_config_bridge_events_total 3"));
+        assert!(rendered.contains("pbc_config_reconnects_total 1"));
+    }
+}
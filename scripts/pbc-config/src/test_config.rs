@@ -0,0 +1,36 @@
+//! Config fixtures the tests build on, so each test spells out only the
+//! fields it is about.
+
+use serde_json::{json, Value};
+
+/// A chain entry whose PBC is at `10.0.0.{100 + index}`
+pub fn chain(index: u8, token_address: &str, bridge_address: &str, decimals: u8) -> Value {
+    json!({
+        "pbc_name": format!("pbc-{}", index),
+        "pbc_endpoint": format!("ws://10.0.0.{}:9944", 100 + index),
+        "http_endpoint": format!("http://10.0.0.{}:9933", 100 + index),
+        "token_address": token_address,
+        "bridge_address": bridge_address,
+        "source_decimals": decimals,
+    })
+}
+
+/// A complete config around `chains` with no relayers, FlareChain at
+/// `10.0.0.100` and `confirmation_blocks` for the given chains
+pub fn config(chains: Value, confirmation_blocks: Value) -> Value {
+    json!({
+        "operator": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+        "relayers": [],
+        "chains": chains,
+        "flarechain": {
+            "endpoint": "ws://10.0.0.100:9944",
+            "http_endpoint": "http://10.0.0.100:9933",
+        },
+        "configuration": {
+            "max_transfer_amount": "1000000000000",
+            "min_transfer_amount": "1000000",
+            "bridge_fee_percent": "0.1",
+            "confirmation_blocks": confirmation_blocks,
+        },
+    })
+}
@@ -0,0 +1,98 @@
+//! Wall-clock timing of connects, storage queries and submissions.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::{debug, info};
+
+/// How long one phase of work took for a chain (or endpoint, for connects)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTiming {
+    pub target: String,
+    pub phase: String,
+    pub duration_ms: u64,
+}
+
+/// Timings collected over the lifetime of one run
+#[derive(Debug, Default)]
+pub struct Timings {
+    entries: Mutex<Vec<PhaseTiming>>,
+}
+
+impl Timings {
+    /// Record the time elapsed since `started` for `target`'s `phase`
+    pub fn record(&self, target: &str, phase: &str, started: Instant) {
+        let elapsed = started.elapsed();
+        debug!("{} {} took {:?}", target, phase, elapsed);
+
+        self.entries.lock().unwrap().push(PhaseTiming {
+            target: target.to_string(),
+            phase: phase.to_string(),
+            duration_ms: elapsed.as_millis() as u64,
+        });
+    }
+
+    pub fn for_target(&self, target: &str) -> Vec<PhaseTiming> {
+        self.entries.lock().unwrap()
+            .iter()
+            .filter(|timing| timing.target == target)
+            .cloned()
+            .collect()
+    }
+
+    /// The single slowest phase recorded
+    pub fn slowest_phase(&self) -> Option<PhaseTiming> {
+        self.entries.lock().unwrap()
+            .iter()
+            .max_by_key(|timing| timing.duration_ms)
+            .cloned()
+    }
+
+    /// The target with the largest total time across all its phases
+    pub fn slowest_target(&self) -> Option<(String, u64)> {
+        let mut totals: Vec<(String, u64)> = Vec::new();
+        for timing in self.entries.lock().unwrap().iter() {
+            match totals.iter_mut().find(|(target, _)| *target == timing.target) {
+                Some((_, total)) => *total += timing.duration_ms,
+                None => totals.push((timing.target.clone(), timing.duration_ms)),
+            }
+        }
+        totals.into_iter().max_by_key(|(_, total)| *total)
+    }
+
+    pub fn log_summary(&self) {
+        if let Some((target, total_ms)) = self.slowest_target() {
+            info!("Slowest endpoint: {} ({} ms total)", target, total_ms);
+        }
+        if let Some(timing) = self.slowest_phase() {
+            info!("Slowest phase: {} {} ({} ms)", timing.target, timing.phase, timing.duration_ms);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(timings: &Timings, target: &str, phase: &str, duration_ms: u64) {
+        timings.entries.lock().unwrap().push(PhaseTiming {
+            target: target.to_string(),
+            phase: phase.to_string(),
+            duration_ms,
+        });
+    }
+
+    #[test]
+    fn test_slowest() {
+        let timings = Timings::default();
+        assert!(timings.slowest_phase().is_none());
+
+        push(&timings, "ethereum", "query:token_mapping", 40);
+        push(&timings, "ethereum", "submit:set_token_mapping", 50);
+        push(&timings, "bitcoin", "submit:set_token_mapping", 70);
+
+        assert_eq!(timings.slowest_target(), Some(("ethereum".to_string(), 90)));
+        assert_eq!(timings.slowest_phase().unwrap().target, "bitcoin");
+        assert_eq!(timings.for_target("ethereum").len(), 2);
+    }
+}
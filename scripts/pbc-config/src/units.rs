@@ -0,0 +1,127 @@
+//! Conversions between human-entered values and runtime types.
+
+use anyhow::{Context, Result};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// Parts per million in one percent
+const PERMILL_PER_PERCENT: u32 = 10_000;
+
+/// Convert a human percent (e.g. `"0.3"`) into a `Permill` (e.g. `3000`).
+///
+/// Rejects values outside `[0, 100]` and values finer than one part per
+/// million, which the runtime would otherwise silently round.
+pub fn percent_to_permill(percent: &str) -> Result<u32> {
+    let value = Decimal::from_str(percent.trim())
+        .context(format!("Invalid percent `{}`, expected a number like 0.3", percent))?;
+    if value.is_sign_negative() || value > Decimal::ONE_HUNDRED {
+        anyhow::bail!("Percent {} is out of range, expected 0 to 100", percent);
+    }
+
+    let permill = value * Decimal::from(PERMILL_PER_PERCENT);
+    if !permill.fract().is_zero() {
+        anyhow::bail!(
+            "Percent {} is more precise than the runtime supports (0.0001% steps)",
+            percent
+        );
+    }
+    permill.to_u32()
+        .context(format!("Percent {} could not be converted to Permill", percent))
+}
+
+/// Parse an amount in the chain's smallest unit (e.g. `"1000000"` or `"1_000_000"`)
+pub fn parse_amount(amount: &str) -> Result<u128> {
+    let digits: String = amount.trim().chars().filter(|c| *c != '_').collect();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        anyhow::bail!(
+            "Invalid amount `{}`, expected a non-negative integer in the smallest unit like 1000000",
+            amount
+        );
+    }
+    digits.parse()
+        .context(format!("Amount `{}` does not fit in a u128", amount))
+}
+
+/// `value` as a decimal without redundant zeros or whitespace (e.g. `" 0.10"` -> `"0.1"`)
+pub fn canonical_decimal(value: &str) -> Result<String> {
+    let decimal = Decimal::from_str(value.trim())
+        .context(format!("Invalid number `{}`", value))?;
+    Ok(decimal.normalize().to_string())
+}
+
+/// Render `amount` base units as whole tokens with `decimals` places,
+/// dropping trailing zeros (e.g. `1500000` with 6 decimals -> `"1.5"`)
+pub fn format_units(amount: u128, decimals: u8) -> String {
+    let digits = format!("{:0>width$}", amount, width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Render a `Permill` back as a human percent (e.g. `3000` -> `"0.3%"`)
+pub fn permill_to_percent(permill: u32) -> String {
+    let percent = Decimal::from(permill) / Decimal::from(PERMILL_PER_PERCENT);
+    format!("{}%", percent.normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_to_permill() {
+        assert_eq!(percent_to_permill("0").unwrap(), 0);
+        assert_eq!(percent_to_permill("100").unwrap(), 1_000_000);
+        assert_eq!(percent_to_permill("0.0001").unwrap(), 1);
+        assert_eq!(percent_to_permill("0.3").unwrap(), 3_000);
+        assert_eq!(percent_to_permill(" 0.1 ").unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_percent_to_permill_rejects_invalid() {
+        assert!(percent_to_permill("100.0001").is_err());
+        assert!(percent_to_permill("-0.1").is_err());
+        assert!(percent_to_permill("0.00001").is_err());
+        assert!(percent_to_permill("abc").is_err());
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("0").unwrap(), 0);
+        assert_eq!(parse_amount("1_000_000").unwrap(), 1_000_000);
+        assert!(parse_amount("-5").is_err());
+        assert!(parse_amount("1.5").is_err());
+        assert!(parse_amount("").is_err());
+        assert!(parse_amount("340282366920938463463374607431768211456").is_err());
+    }
+
+    #[test]
+    fn test_canonical_decimal() {
+        assert_eq!(canonical_decimal(" 0.10").unwrap(), "0.1");
+        assert_eq!(canonical_decimal("1000000").unwrap(), "1000000");
+        assert_eq!(canonical_decimal("2.500").unwrap(), "2.5");
+        assert!(canonical_decimal("1e3x").is_err());
+    }
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(1_500_000, 6), "1.5");
+        assert_eq!(format_units(42, 6), "0.000042");
+        assert_eq!(format_units(100_000_000, 8), "1");
+        assert_eq!(format_units(7, 0), "7");
+        assert_eq!(format_units(u128::MAX, 18), "340282366920938463463.374607431768211455");
+    }
+
+    #[test]
+    fn test_permill_to_percent() {
+        assert_eq!(permill_to_percent(0), "0%");
+        assert_eq!(permill_to_percent(1), "0.0001%");
+        assert_eq!(permill_to_percent(3_000), "0.3%");
+        assert_eq!(permill_to_percent(1_000_000), "100%");
+    }
+}
@@ -0,0 +1,48 @@
+//! Following runtime upgrades that land during a long-running operation.
+
+use subxt::{OnlineClient, PolkadotConfig};
+use tracing::warn;
+
+/// Keeps a client's metadata in step with runtime upgrades until dropped
+pub struct UpgradeFollower(tokio::task::JoinHandle<()>);
+
+impl Drop for UpgradeFollower {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Watch `client`'s node for spec-version changes, swapping in the new
+/// runtime's metadata as each upgrade finalizes so later calls aren't built
+/// against a stale runtime. The client is shared, so every clone sees the update.
+pub fn follow(client: OnlineClient<PolkadotConfig>) -> UpgradeFollower {
+    UpgradeFollower(tokio::spawn(async move {
+        let updater = client.updater();
+        let mut updates = match updater.runtime_updates().await {
+            Ok(updates) => updates,
+            Err(e) => {
+                warn!("Cannot follow runtime upgrades, a mid-run upgrade will not be detected: {}", e);
+                return;
+            }
+        };
+
+        while let Some(update) = updates.next().await {
+            let update = match update {
+                Ok(update) => update,
+                Err(e) => {
+                    warn!("Failed to fetch a runtime upgrade: {}", e);
+                    continue;
+                }
+            };
+            let from = client.runtime_version().spec_version;
+            let to = update.runtime_version().spec_version;
+            // The first item is the current version, which is rejected as unchanged
+            if updater.apply_update(update).is_ok() {
+                warn!(
+                    "Runtime upgraded from spec version {} to {} mid-run; refreshed metadata for the remaining calls",
+                    from, to
+                );
+            }
+        }
+    }))
+}
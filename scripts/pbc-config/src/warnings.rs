@@ -0,0 +1,48 @@
+//! Warnings about the config or on-chain state, which `--strict` turns into errors.
+//!
+//! Problems that don't stop an operation are reported through [`warn`] rather
+//! than `tracing::warn!`, so the same command can run leniently at a terminal
+//! and strictly in CI. Progress notices (retries, failover, reconnects) and
+//! warnings about what the operator explicitly asked for stay plain logs.
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+/// Apply `--strict`
+pub fn init(strict: bool) {
+    STRICT.store(strict, Ordering::Relaxed);
+}
+
+/// Whether `--strict` is on
+pub fn strict() -> bool {
+    STRICT.load(Ordering::Relaxed)
+}
+
+/// Log `message` as a warning, or with `--strict` fail with it
+pub fn warn(message: impl Display) -> Result<()> {
+    warn_with(strict(), message)
+}
+
+/// [`warn`] with `strict` given rather than taken from `--strict`
+fn warn_with(strict: bool, message: impl Display) -> Result<()> {
+    if strict {
+        anyhow::bail!("{} (--strict)", message);
+    }
+    tracing::warn!("{}", message);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict() {
+        assert!(warn_with(false, "stale rate").is_ok());
+        assert_eq!(warn_with(true, "stale rate").unwrap_err().to_string(), "stale rate (--strict)");
+    }
+}